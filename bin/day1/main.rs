@@ -1,13 +1,69 @@
+use aoc23::answer::Answer;
+use memmap2::Mmap;
 use std::{
+    env,
     fs::File,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead, BufReader, IsTerminal},
     path::Path,
+    str, thread,
 };
 
-fn find_first_number(line: &String, reverse: bool) -> Option<(usize, u32)> {
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// A digit or number word found in a line, as a byte range of the line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Match {
+    start: usize,
+    end: usize,
+    value: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Digit,
+    Word,
+}
+
+/// Every digit and number word in a line as `(start, end, value, kind)`, in
+/// order of where they start. Overlapping words are all reported, so
+/// "oneight" gives both one and eight.
+struct Tokens<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = (usize, usize, u32, Kind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(c) = self.line[self.pos..].chars().next() {
+            let start = self.pos;
+            let rest = &self.line[start..];
+            self.pos += c.len_utf8();
+
+            if let Some(d) = c.to_digit(10) {
+                return Some((start, start + 1, d, Kind::Digit));
+            }
+
+            if let Some(i) = WORDS.iter().position(|w| rest.starts_with(w)) {
+                return Some((start, start + WORDS[i].len(), i as u32 + 1, Kind::Word));
+            }
+        }
+
+        None
+    }
+}
+
+fn tokens(line: &str) -> Tokens<'_> {
+    Tokens { line, pos: 0 }
+}
+
+pub(crate) fn find_first_number(line: &str, reverse: bool) -> Option<(usize, u32)> {
     let index_str = match reverse {
-        true => line.rfind(char::is_numeric),
-        false => line.find(char::is_numeric),
+        true => line.rfind(|c: char| c.is_ascii_digit()),
+        false => line.find(|c: char| c.is_ascii_digit()),
     };
 
     if let Some(i) = index_str {
@@ -16,26 +72,25 @@ fn find_first_number(line: &String, reverse: bool) -> Option<(usize, u32)> {
             index = line.len() - i - 1;
         }
 
-        return Some((index, line.chars().nth(i).unwrap().to_digit(10).unwrap()));
+        return Some((index, u32::from(line.as_bytes()[i] - b'0')));
     }
 
     None
 }
 
-fn find_first_word_number(line: &String, reverse: bool) -> Option<(usize, u32)> {
-    let words: Vec<String> = [
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ]
-    .iter()
-    .map(|s| s.to_string())
-    .map(|s| {
-        if reverse {
-            s.chars().rev().collect()
-        } else {
-            s
-        }
-    })
-    .collect();
+#[allow(clippy::unnecessary_sort_by, clippy::len_zero)]
+pub(crate) fn find_first_word_number(line: &str, reverse: bool) -> Option<(usize, u32)> {
+    let words: Vec<String> = WORDS
+        .iter()
+        .map(|s| s.to_string())
+        .map(|s| {
+            if reverse {
+                s.chars().rev().collect()
+            } else {
+                s
+            }
+        })
+        .collect();
 
     let processed_line = if reverse {
         line.chars().rev().collect::<String>()
@@ -68,55 +123,244 @@ fn find_first_word_number(line: &String, reverse: bool) -> Option<(usize, u32)>
     None
 }
 
-fn find_number_by_word_and_digit(line: &String, reverse: bool) -> Option<u32> {
-    match (
-        find_first_number(&line, reverse),
-        find_first_word_number(&line, reverse),
-    ) {
-        (None, Some((_, n))) => Some(n),
-        (Some((_, n)), None) => Some(n),
-        (Some((i0, n0)), Some((i1, n1))) => {
+/// The first (or with `reverse` the last) digit or number word in `line`,
+/// picked the same way as `find_number_by_word_and_digit`.
+fn find_match(line: &str, reverse: bool) -> Option<Match> {
+    let to_start = |index: usize| {
+        if reverse {
+            line.len() - index - 1
+        } else {
+            index
+        }
+    };
+
+    let digit = find_first_number(line, reverse).map(|(i, n)| {
+        (
+            i,
+            Match {
+                start: to_start(i),
+                end: to_start(i) + 1,
+                value: n,
+            },
+        )
+    });
+    let word = find_first_word_number(line, reverse).map(|(i, n)| {
+        (
+            i,
+            Match {
+                start: to_start(i),
+                end: to_start(i) + WORDS[n as usize - 1].len(),
+                value: n,
+            },
+        )
+    });
+
+    match (digit, word) {
+        (None, Some((_, m))) => Some(m),
+        (Some((_, m)), None) => Some(m),
+        (Some((i0, m0)), Some((i1, m1))) => {
             if i0 < i1 {
-                Some(n0)
+                Some(m0)
             } else {
-                Some(n1)
+                Some(m1)
             }
         }
         (None, None) => None,
     }
 }
 
+pub(crate) fn find_number_by_word_and_digit(line: &str, reverse: bool) -> Option<u32> {
+    find_match(line, reverse).map(|m| m.value)
+}
+
+/// Marks the first match with `[ ]` and the last with `{ }`, or with green,
+/// blue and magenta (for characters in both) when `color` is set.
+fn highlight(line: &str, first: Option<Match>, last: Option<Match>, color: bool) -> String {
+    let inside = |m: Option<Match>, i: usize| m.is_some_and(|m| m.start <= i && i < m.end);
+    let is_start = |m: Option<Match>, i: usize| m.is_some_and(|m| m.start == i);
+    let is_end = |m: Option<Match>, i: usize| m.is_some_and(|m| m.end == i);
+
+    let mut out = String::new();
+    let chars = line
+        .char_indices()
+        .map(|(i, c)| (i, Some(c)))
+        .chain([(line.len(), None)]);
+
+    for (i, c) in chars {
+        if color {
+            let code = match (inside(first, i), inside(last, i)) {
+                (true, true) => Some(35),
+                (true, false) => Some(32),
+                (false, true) => Some(34),
+                (false, false) => None,
+            };
+            if let (Some(code), Some(c)) = (code, c) {
+                out.push_str(&format!("\x1b[1;{}m{}\x1b[0m", code, c));
+            } else if let Some(c) = c {
+                out.push(c);
+            }
+        } else {
+            if is_end(last, i) {
+                out.push('}');
+            }
+            if is_end(first, i) {
+                out.push(']');
+            }
+            if is_start(first, i) {
+                out.push('[');
+            }
+            if is_start(last, i) {
+                out.push('{');
+            }
+            if let Some(c) = c {
+                out.push(c);
+            }
+        }
+    }
+
+    out
+}
+
+pub(crate) fn explain(line: &str, color: bool) -> String {
+    let first = find_match(line, false);
+    let last = find_match(line, true);
+    let span = |m: Option<Match>| match m {
+        Some(m) => format!("{} at {}..{}", m.value, m.start, m.end),
+        None => "none".to_string(),
+    };
+
+    let all = tokens(line)
+        .map(|(start, end, value, kind)| match kind {
+            Kind::Digit => format!("{}@{}", value, start),
+            Kind::Word => format!("{}@{}..{}", &line[start..end], start, end),
+        })
+        .collect::<Vec<String>>();
+
+    format!(
+        "{}  first {}  last {}  digit {:?} {:?}  word {:?} {:?}  tokens [{}]",
+        highlight(line, first, last, color),
+        span(first),
+        span(last),
+        find_first_number(line, false),
+        find_first_number(line, true),
+        find_first_word_number(line, false),
+        find_first_word_number(line, true),
+        all.join(" "),
+    )
+}
+
+/// The two-digit calibration value of a line, from its first and last number.
+pub(crate) fn calibration_value(line: &str) -> Option<u32> {
+    let first = find_number_by_word_and_digit(line, false)?;
+    let last = find_number_by_word_and_digit(line, true)?;
+    Some(first * 10 + last)
+}
+
+/// Splits `data` into at most `parts` chunks, each ending on a line boundary.
+fn split_chunks(data: &[u8], parts: usize) -> Vec<&[u8]> {
+    let size = data.len().div_ceil(parts.max(1)).max(1);
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let end = if rest.len() <= size {
+            rest.len()
+        } else {
+            rest[size - 1..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(rest.len(), |i| size + i)
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// Sums the calibration values of every line in a chunk.
+fn sum_chunk(chunk: &[u8]) -> io::Result<Answer> {
+    let body = chunk.strip_suffix(b"\n").unwrap_or(chunk);
+    let mut sum = Answer::ZERO;
+    for raw in body.split(|&b| b == b'\n') {
+        let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        let line =
+            str::from_utf8(raw).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let value = calibration_value(line).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("no calibration value in line {:?}", line),
+            )
+        })?;
+        sum = sum.checked_add(value, "calibration sum")?;
+    }
+    Ok(sum)
+}
+
+/// Sums all calibration values in `data`, splitting the work across `workers` threads.
+pub(crate) fn parallel_sum(data: &[u8], workers: usize) -> io::Result<Answer> {
+    let chunks = split_chunks(data, workers);
+    thread::scope(|s| {
+        let handles = chunks
+            .into_iter()
+            .map(|chunk| s.spawn(move || sum_chunk(chunk)))
+            .collect::<Vec<_>>();
+        handles.into_iter().try_fold(Answer::ZERO, |acc, h| {
+            Ok(acc.checked_add(h.join().unwrap()?, "calibration sum")?)
+        })
+    })
+}
+
 fn main() -> io::Result<()> {
     println!("day1");
 
-    let path = Path::new("./bin/day1/input");
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let path = Path::new(
+        args.iter()
+            .find(|a| !a.starts_with("--"))
+            .map_or("./bin/day1/input", String::as_str),
+    );
+
+    let explain_mode = args.iter().any(|a| a == "--explain");
+    if args.iter().any(|a| a == "--parallel") {
+        if explain_mode {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--explain cannot be combined with --parallel",
+            ));
+        }
+
+        let file = File::open(path)?;
+        // SAFETY: the input is only read, and is not expected to change while mapped.
+        let map = unsafe { Mmap::map(&file)? };
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        println!("acc {}", parallel_sum(&map, workers)?);
+        return Ok(());
+    }
+
     let file = File::open(path).unwrap();
     let lines = BufReader::new(file).lines();
 
-    let mut acc: u32 = 0;
-    for l in lines {
-        let line = l?;
+    let color = io::stdout().is_terminal();
 
-        let mut num = "".to_string();
+    let mut acc = Answer::ZERO;
+    for (i, l) in lines.enumerate() {
+        let line = l?;
 
-        let first_number = find_number_by_word_and_digit(&line, false);
-        if let Some(n) = first_number {
-            num.push(n.to_string().chars().nth(0).unwrap());
+        if explain_mode {
+            println!("{}", explain(&line, color));
         }
 
-        let last_number = find_number_by_word_and_digit(&line, true);
-        if let Some(n) = last_number {
-            num.push(n.to_string().chars().nth(0).unwrap());
-        }
+        let num = calibration_value(&line).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("no calibration value in line {}", i + 1),
+            )
+        })?;
 
-        println!(
-            "{} {} {} {}",
-            line,
-            num,
-            first_number.unwrap(),
-            last_number.unwrap()
-        );
-        acc += num.parse::<u32>().unwrap();
+        if !explain_mode {
+            println!("{} {} {} {}", line, num, num / 10, num % 10);
+        }
+        acc = acc.checked_add(num, format_args!("calibration sum at line {}", i + 1))?;
     }
 
     println!("acc {}", acc);
@@ -125,8 +369,20 @@ fn main() -> io::Result<()> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_to_owned)]
 mod tests {
-    use crate::{find_first_number, find_first_word_number, find_number_by_word_and_digit};
+    use crate::{
+        calibration_value, explain, find_first_number, find_first_word_number, find_match,
+        find_number_by_word_and_digit, highlight, parallel_sum, split_chunks, tokens, Kind, Match,
+        WORDS,
+    };
+    use aoc23::{
+        differential::Differential,
+        generate::{self, Day1Config},
+        random::Rng,
+        reference,
+    };
+    use std::str;
 
     #[test]
     fn test_find_first_number() {
@@ -285,4 +541,322 @@ mod tests {
             Some(6)
         );
     }
+
+    #[test]
+    fn test_find_match() {
+        assert_eq!(
+            find_match("zoneight234", false),
+            Some(Match {
+                start: 1,
+                end: 4,
+                value: 1
+            })
+        );
+        assert_eq!(
+            find_match("zoneight234", true),
+            Some(Match {
+                start: 10,
+                end: 11,
+                value: 4
+            })
+        );
+        assert_eq!(
+            find_match("xtwone3four", true),
+            Some(Match {
+                start: 7,
+                end: 11,
+                value: 4
+            })
+        );
+        assert_eq!(
+            find_match("eightwo", true),
+            Some(Match {
+                start: 4,
+                end: 7,
+                value: 2
+            })
+        );
+        assert_eq!(find_match("abc", false), None);
+    }
+
+    #[test]
+    fn test_highlight_brackets() {
+        let line = "eightwo";
+        let first = find_match(line, false);
+        let last = find_match(line, true);
+        assert_eq!(highlight(line, first, last, false), "[eigh{t]wo}");
+
+        let line = "treb7uchet";
+        let first = find_match(line, false);
+        let last = find_match(line, true);
+        assert_eq!(highlight(line, first, last, false), "treb[{7}]uchet");
+
+        let line = "a1b2c3d4e5f";
+        let first = find_match(line, false);
+        let last = find_match(line, true);
+        assert_eq!(highlight(line, first, last, false), "a[1]b2c3d4e{5}f");
+
+        assert_eq!(highlight("abc", None, None, false), "abc");
+    }
+
+    #[test]
+    fn test_highlight_color() {
+        let line = "1ab2";
+        let first = find_match(line, false);
+        let last = find_match(line, true);
+        assert_eq!(
+            highlight(line, first, last, true),
+            "\x1b[1;32m1\x1b[0mab\x1b[1;34m2\x1b[0m"
+        );
+
+        let line = "x7";
+        let m = find_match(line, false);
+        assert_eq!(highlight(line, m, m, true), "x\x1b[1;35m7\x1b[0m");
+    }
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(
+            explain("zoneight234", false),
+            "z[one]ight23{4}  first 1 at 1..4  last 4 at 10..11  \
+             digit Some((8, 2)) Some((0, 4))  word Some((1, 1)) Some((7, 8))  \
+             tokens [one@1..4 eight@3..8 2@8 3@9 4@10]"
+        );
+        assert_eq!(
+            explain("abc", false),
+            "abc  first none  last none  digit None None  word None None  tokens []"
+        );
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens("eightwo3").collect::<Vec<(usize, usize, u32, Kind)>>(),
+            vec![
+                (0, 5, 8, Kind::Word),
+                (4, 7, 2, Kind::Word),
+                (7, 8, 3, Kind::Digit)
+            ]
+        );
+        assert_eq!(
+            tokens("twoneighthree").map(|t| t.2).collect::<Vec<u32>>(),
+            vec![2, 1, 8, 3]
+        );
+        assert_eq!(
+            tokens("é1nine").collect::<Vec<(usize, usize, u32, Kind)>>(),
+            vec![(2, 3, 1, Kind::Digit), (3, 7, 9, Kind::Word)]
+        );
+        assert_eq!(tokens("abc").count(), 0);
+        assert_eq!(tokens("").count(), 0);
+    }
+
+    #[test]
+    fn test_tokens_alternative_rules() {
+        let line = "4nineeightseven2";
+        let values = tokens(line).map(|t| t.2).collect::<Vec<u32>>();
+        assert_eq!(values.iter().sum::<u32>(), 30);
+        assert_eq!(values[values.len() / 2], 8);
+
+        let digits_only = tokens(line)
+            .filter(|t| t.3 == Kind::Digit)
+            .map(|t| t.2)
+            .collect::<Vec<u32>>();
+        assert_eq!(digits_only, vec![4, 2]);
+    }
+
+    #[test]
+    fn test_tokens_match_first_and_last() {
+        let mut rng = Rng::new(44);
+        let pieces = ["x", "a", "e", "n", "o", "1", "7"]
+            .iter()
+            .chain(WORDS.iter())
+            .copied()
+            .collect::<Vec<&str>>();
+
+        for _ in 0..2000 {
+            let line = (0..rng.range(0..8))
+                .map(|_| *rng.choose(&pieces))
+                .collect::<String>();
+
+            let all = tokens(&line).map(|t| t.2).collect::<Vec<u32>>();
+            assert_eq!(
+                all.first().copied(),
+                find_number_by_word_and_digit(&line, false),
+                "{}",
+                line
+            );
+            assert_eq!(
+                all.last().copied(),
+                find_number_by_word_and_digit(&line, true),
+                "{}",
+                line
+            );
+        }
+    }
+
+    // Example from part 2, summing to 281.
+    const EXAMPLE: &str = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+                           4nineeightseven2\nzoneight234\n7pqrstsixteen\n";
+
+    #[test]
+    fn test_calibration_value() {
+        assert_eq!(calibration_value("two1nine"), Some(29));
+        assert_eq!(calibration_value("treb7uchet"), Some(77));
+        assert_eq!(calibration_value("xyz"), None);
+    }
+
+    #[test]
+    fn test_split_chunks_on_line_boundaries() {
+        let data = EXAMPLE.as_bytes();
+        for parts in 1..=10 {
+            let chunks = split_chunks(data, parts);
+            assert!(chunks.len() <= parts);
+            assert_eq!(chunks.concat(), data);
+            assert!(chunks.iter().all(|c| c.ends_with(b"\n")));
+        }
+    }
+
+    #[test]
+    fn test_parallel_sum_example() {
+        for workers in 1..=10 {
+            assert_eq!(
+                parallel_sum(EXAMPLE.as_bytes(), workers).unwrap().value(),
+                281
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_sum_crlf_and_no_trailing_newline() {
+        let data = "two1nine\r\neightwothree\r\nabcone2threexyz";
+        for workers in 1..=4 {
+            assert_eq!(
+                parallel_sum(data.as_bytes(), workers).unwrap().value(),
+                29 + 83 + 13
+            );
+        }
+        assert_eq!(parallel_sum(b"", 4).unwrap().value(), 0);
+    }
+
+    #[test]
+    fn test_parallel_sum_rejects_line_without_value() {
+        assert!(parallel_sum(b"two1nine\nxyz\n", 2).is_err());
+        assert!(parallel_sum(b"two1nine\n\xff\n", 2).is_err());
+    }
+
+    #[test]
+    fn test_parallel_sum_matches_sequential() {
+        let mut rng = Rng::new(45);
+        let pieces = ["one", "7", "eight", "abc", "twone", "x", "3", "nine"];
+        let mut data = String::new();
+        let mut expected = 0u64;
+        for _ in 0..500 {
+            let mut line = (*rng.choose(&pieces)).to_string();
+            for _ in 0..rng.range(0..6) {
+                let piece = rng.choose(&pieces);
+                line.push_str(piece);
+            }
+            line.push('5');
+            expected += u64::from(calibration_value(&line).unwrap());
+            data.push_str(&line);
+            data.push('\n');
+        }
+        for workers in [1, 2, 3, 7, 16, 64] {
+            assert_eq!(
+                parallel_sum(data.as_bytes(), workers).unwrap().value(),
+                expected
+            );
+        }
+    }
+
+    /// A line from the generator, or a short run of letters that spell parts
+    /// of number words, mixed with a few digits.
+    fn random_line(rng: &mut Rng) -> String {
+        if rng.chance(50) {
+            let config = Day1Config {
+                lines: 1,
+                ..Day1Config::default()
+            };
+            return generate::day1(rng.next_u64(), &config)
+                .input
+                .trim_end()
+                .to_string();
+        }
+
+        let alphabet = "efghinorstuvwxz123456789".chars().collect::<Vec<char>>();
+        (0..rng.range(0..16))
+            .map(|_| *rng.choose(&alphabet))
+            .collect()
+    }
+
+    #[test]
+    fn test_digits_agree_with_reference() {
+        Differential::new()
+            .register("reference", |l: &String| reference::calibration(l, false))
+            .register("find_first_number", |l: &String| {
+                match (find_first_number(l, false), find_first_number(l, true)) {
+                    (Some((_, first)), Some((_, last))) => u64::from(first * 10 + last),
+                    _ => 0,
+                }
+            })
+            .assert_agree(48, 2000, random_line);
+    }
+
+    #[test]
+    fn test_words_agree_with_reference() {
+        Differential::new()
+            .register("reference", |l: &String| reference::calibration(l, true))
+            .register("find_match", |l: &String| {
+                calibration_value(l).map_or(0, u64::from)
+            })
+            .register("tokens", |l: &String| {
+                let values = tokens(l).map(|(_, _, v, _)| v).collect::<Vec<u32>>();
+                match (values.first(), values.last()) {
+                    (Some(first), Some(last)) => u64::from(first * 10 + last),
+                    _ => 0,
+                }
+            })
+            .assert_agree(48, 2000, random_line);
+    }
+
+    /// Checks a fuzzer input for the finders: no line may panic, values must
+    /// match the reference, and CRLF line endings or a missing final newline
+    /// must not change the total.
+    fn check(data: &[u8]) {
+        let Ok(text) = str::from_utf8(data) else {
+            return;
+        };
+
+        for line in text.lines() {
+            assert_eq!(
+                calibration_value(line).map_or(0, u64::from),
+                reference::calibration(line, true),
+                "{:?}",
+                line
+            );
+            explain(line, false);
+            explain(line, true);
+        }
+
+        if let Ok(total) = parallel_sum(data, 3) {
+            let crlf = text.replace('\n', "\r\n");
+            assert_eq!(parallel_sum(crlf.as_bytes(), 2).unwrap(), total);
+            let trimmed = text.strip_suffix('\n').unwrap_or(text);
+            assert_eq!(parallel_sum(trimmed.as_bytes(), 1).unwrap(), total);
+        }
+    }
+
+    // Inputs that used to panic: numeric characters that are not ASCII
+    // digits, and multi-byte characters before a digit.
+    const REPRODUCERS: [&str; 4] = ["½", "é1", "٣", "twoé1"];
+
+    #[test]
+    fn test_fuzz_reproducers() {
+        for input in REPRODUCERS {
+            check(input.as_bytes());
+        }
+        assert_eq!(calibration_value("é1"), Some(11));
+        assert_eq!(calibration_value("½"), None);
+        assert_eq!(calibration_value("twoé1"), Some(21));
+    }
 }
@@ -0,0 +1,276 @@
+use std::io;
+
+use aoc23::{
+    geometry,
+    grid::{Direction, Grid},
+    input,
+};
+
+const PIPES: [char; 6] = ['|', '-', 'L', 'J', '7', 'F'];
+
+fn connections(pipe: char) -> Option<[Direction; 2]> {
+    match pipe {
+        '|' => Some([Direction::North, Direction::South]),
+        '-' => Some([Direction::East, Direction::West]),
+        'L' => Some([Direction::North, Direction::East]),
+        'J' => Some([Direction::North, Direction::West]),
+        '7' => Some([Direction::South, Direction::West]),
+        'F' => Some([Direction::South, Direction::East]),
+        _ => None,
+    }
+}
+
+fn connects(pipe: char, dir: Direction) -> bool {
+    connections(pipe).is_some_and(|c| c.contains(&dir))
+}
+
+struct Maze {
+    grid: Grid<char>,
+    start: (usize, usize),
+}
+
+impl Maze {
+    fn new(mut grid: Grid<char>) -> Option<Maze> {
+        let start = grid.find(|c| *c == 'S')?;
+        let pipe = Maze::infer_start_pipe(&grid, start)?;
+        grid.set(start.0, start.1, pipe);
+
+        Some(Maze { grid, start })
+    }
+
+    fn infer_start_pipe(grid: &Grid<char>, (x, y): (usize, usize)) -> Option<char> {
+        let dirs = Direction::ALL
+            .iter()
+            .filter(|dir| {
+                grid.step(x, y, **dir)
+                    .is_some_and(|(nx, ny)| connects(*grid.get(nx, ny), dir.opposite()))
+            })
+            .copied()
+            .collect::<Vec<Direction>>();
+
+        if dirs.len() != 2 {
+            return None;
+        }
+
+        PIPES
+            .iter()
+            .find(|p| connects(**p, dirs[0]) && connects(**p, dirs[1]))
+            .copied()
+    }
+
+    fn trace_loop(&self) -> Option<Vec<(usize, usize)>> {
+        let mut path = Vec::new();
+        let (mut x, mut y) = self.start;
+        let mut dir = connections(*self.grid.get(x, y))?[0];
+
+        loop {
+            path.push((x, y));
+            (x, y) = self.grid.step(x, y, dir)?;
+            if (x, y) == self.start {
+                break;
+            }
+
+            let [a, b] = connections(*self.grid.get(x, y))?;
+            dir = if a == dir.opposite() {
+                b
+            } else if b == dir.opposite() {
+                a
+            } else {
+                return None;
+            };
+        }
+
+        Some(path)
+    }
+
+    fn enclosed_by_area(&self, path: &[(usize, usize)]) -> Option<i64> {
+        let vertices = path
+            .iter()
+            .map(|(x, y)| (*x as i64, *y as i64))
+            .collect::<Vec<(i64, i64)>>();
+
+        geometry::pick_interior(
+            geometry::shoelace_double_area(&vertices)?,
+            path.len() as i64,
+        )
+    }
+
+    fn enclosed_by_scanline(&self, path: &[(usize, usize)]) -> usize {
+        let mut on_loop = Grid::filled(self.grid.width(), self.grid.height(), false);
+        for (x, y) in path {
+            on_loop.set(*x, *y, true);
+        }
+
+        let mut count = 0;
+        for y in 0..self.grid.height() {
+            let mut inside = false;
+            for x in 0..self.grid.width() {
+                if *on_loop.get(x, y) {
+                    if connects(*self.grid.get(x, y), Direction::North) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day10");
+
+    let lines = input::lines("./bin/day10/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = Grid::from_lines(&lines);
+    grid.ensure_rectangular()?;
+
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let maze =
+        Maze::new(grid).ok_or_else(|| invalid("no start pipe with two connections found"))?;
+    let path = maze
+        .trace_loop()
+        .ok_or_else(|| invalid("start is not on a closed loop"))?;
+
+    println!("loop length {}", path.len());
+    println!("farthest {}", path.len() / 2);
+    println!(
+        "enclosed by area {}",
+        maze.enclosed_by_area(&path)
+            .ok_or_else(|| invalid("enclosed area overflows"))?
+    );
+    println!("enclosed by scanline {}", maze.enclosed_by_scanline(&path));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(lines: &[&str]) -> Maze {
+        let grid = Grid::from_lines(lines);
+        assert!(grid.verify());
+        Maze::new(grid).unwrap()
+    }
+
+    fn enclosed(maze: &Maze) -> usize {
+        let path = maze.trace_loop().unwrap();
+        let by_area = maze.enclosed_by_area(&path);
+        let by_scanline = maze.enclosed_by_scanline(&path);
+        assert_eq!(by_area, Some(by_scanline as i64));
+        by_scanline
+    }
+
+    #[test]
+    fn test_infer_start_pipe() {
+        let maze = load(&[".....", ".S-7.", ".|.|.", ".L-J.", "....."]);
+        assert_eq!(maze.start, (1, 1));
+        assert_eq!(*maze.grid.get(1, 1), 'F');
+
+        let maze = load(&["..F7.", ".FJ|.", "SJ.L7", "|F--J", "LJ..."]);
+        assert_eq!(maze.start, (0, 2));
+        assert_eq!(*maze.grid.get(0, 2), 'F');
+    }
+
+    #[test]
+    fn test_farthest_simple() {
+        let maze = load(&["-L|F7", "7S-7|", "L|7||", "-L-J|", "L|-JF"]);
+        assert_eq!(maze.trace_loop().unwrap().len() / 2, 4);
+    }
+
+    #[test]
+    fn test_farthest_complex() {
+        let maze = load(&["7-F7-", ".FJ|7", "SJLL7", "|F--J", "LJ.LJ"]);
+        assert_eq!(maze.trace_loop().unwrap().len() / 2, 8);
+    }
+
+    #[test]
+    fn test_enclosed_simple() {
+        let maze = load(&[
+            "...........",
+            ".S-------7.",
+            ".|F-----7|.",
+            ".||.....||.",
+            ".||.....||.",
+            ".|L-7.F-J|.",
+            ".|..|.|..|.",
+            ".L--J.L--J.",
+            "...........",
+        ]);
+        assert_eq!(enclosed(&maze), 4);
+    }
+
+    #[test]
+    fn test_enclosed_squeeze() {
+        let maze = load(&[
+            "..........",
+            ".S------7.",
+            ".|F----7|.",
+            ".||....||.",
+            ".||....||.",
+            ".|L-7F-J|.",
+            ".|..||..|.",
+            ".L--JL--J.",
+            "..........",
+        ]);
+        assert_eq!(enclosed(&maze), 4);
+    }
+
+    #[test]
+    fn test_enclosed_larger() {
+        let maze = load(&[
+            ".F----7F7F7F7F-7....",
+            ".|F--7||||||||FJ....",
+            ".||.FJ||||||||L7....",
+            "FJL7L7LJLJ||LJ.L-7..",
+            "L--J.L7...LJS7F-7L7.",
+            "....F-J..F7FJ|L7L7L7",
+            "....L7.F7||L7|.L7L7|",
+            ".....|FJLJ|FJ|F7|.LJ",
+            "....FJL-7.||.||||...",
+            "....L---J.LJ.LJLJ...",
+        ]);
+        assert_eq!(enclosed(&maze), 8);
+    }
+
+    #[test]
+    fn test_enclosed_junk() {
+        let maze = load(&[
+            "FF7FSF7F7F7F7F7F---7",
+            "L|LJ||||||||||||F--J",
+            "FL-7LJLJ||||||LJL-77",
+            "F--JF--7||LJLJ7F7FJ-",
+            "L---JF-JLJ.||-FJLJJ7",
+            "|F|F-JF---7F7-L7L|7|",
+            "|FFJF7L7F-JF7|JL---7",
+            "7-L-JL7||F7|L7F-7F7|",
+            "L.L7LFJ|||||FJL7||LJ",
+            "L7JLJL-JLJLJL--JLJ.L",
+        ]);
+        assert_eq!(enclosed(&maze), 10);
+    }
+
+    #[test]
+    fn test_no_start() {
+        assert!(Maze::new(Grid::from_lines(&["F7", "LJ"])).is_none());
+    }
+
+    #[test]
+    fn test_unresolvable_start() {
+        // S connects to one, three, four and no neighbours
+        for lines in [
+            ["S-7", "...", "..."],
+            [".|.", "-S-", "..."],
+            [".|.", "-S-", ".|."],
+            ["...", ".S.", "..."],
+        ] {
+            let grid = Grid::from_lines(&lines);
+            let start = grid.find(|c| *c == 'S').unwrap();
+            assert_eq!(Maze::infer_start_pipe(&grid, start), None, "{:?}", lines);
+            assert!(Maze::new(grid).is_none());
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::io;
+
+use aoc23::{
+    answer::{Answer, AnswerError},
+    grid::Grid,
+    input,
+};
+
+struct Universe {
+    galaxies: Vec<(usize, usize)>,
+    empty_rows: Vec<usize>,
+    empty_cols: Vec<usize>,
+}
+
+impl Universe {
+    fn new(grid: &Grid<char>) -> Universe {
+        let galaxies = grid
+            .positions()
+            .filter(|&(x, y)| *grid.get(x, y) == '#')
+            .collect::<Vec<(usize, usize)>>();
+
+        let mut row_used = vec![false; grid.height()];
+        let mut col_used = vec![false; grid.width()];
+        for (x, y) in &galaxies {
+            row_used[*y] = true;
+            col_used[*x] = true;
+        }
+
+        Universe {
+            galaxies,
+            empty_rows: Universe::prefix_empty(&row_used),
+            empty_cols: Universe::prefix_empty(&col_used),
+        }
+    }
+
+    /// Number of empty lines before each index.
+    fn prefix_empty(used: &[bool]) -> Vec<usize> {
+        used.iter()
+            .scan(0, |count, used| {
+                let before = *count;
+                if !used {
+                    *count += 1;
+                }
+                Some(before)
+            })
+            .collect::<Vec<usize>>()
+    }
+
+    /// Galaxy positions after each empty row and column grows to `factor`
+    /// lines. A factor of 0 would remove them, which is not supported.
+    fn expanded(&self, factor: u64) -> io::Result<Vec<(u64, u64)>> {
+        let Some(growth) = factor.checked_sub(1) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "expansion factor must be at least 1",
+            ));
+        };
+
+        let expand = |i: usize, empty: usize| {
+            Answer::new(empty as u64)
+                .checked_mul(growth, "expanded galaxy")?
+                .checked_add(i, "expanded galaxy")
+                .map(Answer::value)
+        };
+
+        self.galaxies
+            .iter()
+            .map(|&(x, y)| {
+                Ok((
+                    expand(x, self.empty_cols[x])?,
+                    expand(y, self.empty_rows[y])?,
+                ))
+            })
+            .collect::<io::Result<Vec<(u64, u64)>>>()
+    }
+
+    fn distance_sum(&self, factor: u64) -> io::Result<Answer> {
+        let (xs, ys): (Vec<u64>, Vec<u64>) = self.expanded(factor)?.into_iter().unzip();
+        Ok(axis_distance_sum(xs)?.checked_add(axis_distance_sum(ys)?, "distance sum")?)
+    }
+}
+
+/// Sum of `|a - b|` over all pairs, by sorting and keeping a running sum of
+/// the smaller values.
+fn axis_distance_sum(mut values: Vec<u64>) -> Result<Answer, AnswerError> {
+    values.sort_unstable();
+
+    let mut sum = Answer::ZERO;
+    let mut prefix = Answer::ZERO;
+    for (i, &v) in values.iter().enumerate() {
+        let distances = Answer::new(v).checked_mul(i, "distance sum")?.value() - prefix.value();
+        sum = sum.checked_add(distances, "distance sum")?;
+        prefix = prefix.checked_add(v, "distance sum")?;
+    }
+
+    Ok(sum)
+}
+
+fn main() -> io::Result<()> {
+    println!("day11");
+
+    let lines = input::lines("./bin/day11/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = Grid::from_lines(&lines);
+    grid.ensure_rectangular()?;
+
+    let universe = Universe::new(&grid);
+    println!("galaxies {}", universe.galaxies.len());
+    println!("distance sum x2 {}", universe.distance_sum(2)?);
+    println!(
+        "distance sum x1000000 {}",
+        universe.distance_sum(1_000_000)?
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ...#......
+    // .......#..
+    // #.........
+    // ..........
+    // ......#...
+    // .#........
+    // .........#
+    // ..........
+    // .......#..
+    // #...#.....
+
+    fn example() -> Universe {
+        Universe::new(&Grid::from_lines(&[
+            "...#......",
+            ".......#..",
+            "#.........",
+            "..........",
+            "......#...",
+            ".#........",
+            ".........#",
+            "..........",
+            ".......#..",
+            "#...#.....",
+        ]))
+    }
+
+    fn naive_distance_sum(galaxies: &[(u64, u64)]) -> u64 {
+        let mut sum = 0;
+        for i in 0..galaxies.len() {
+            for j in i + 1..galaxies.len() {
+                sum +=
+                    galaxies[i].0.abs_diff(galaxies[j].0) + galaxies[i].1.abs_diff(galaxies[j].1);
+            }
+        }
+
+        sum
+    }
+
+    #[test]
+    fn test_empty_lines() {
+        let universe = example();
+        assert_eq!(universe.galaxies.len(), 9);
+        assert_eq!(universe.empty_rows, vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2]);
+        assert_eq!(universe.empty_cols, vec![0, 0, 0, 1, 1, 1, 2, 2, 2, 3]);
+    }
+
+    #[test]
+    fn test_expanded() {
+        let universe = example();
+        let expanded = universe.expanded(2).unwrap();
+        assert_eq!(expanded[0], (4, 0));
+        assert_eq!(expanded[8], (5, 11));
+    }
+
+    #[test]
+    fn test_distance_sum() {
+        let universe = example();
+        assert_eq!(universe.distance_sum(2).unwrap().value(), 374);
+        assert_eq!(universe.distance_sum(10).unwrap().value(), 1030);
+        assert_eq!(universe.distance_sum(100).unwrap().value(), 8410);
+    }
+
+    #[test]
+    fn test_zero_factor() {
+        let universe = example();
+        assert_eq!(
+            universe.expanded(0).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert!(universe.distance_sum(0).is_err());
+        assert_eq!(
+            universe.expanded(1).unwrap(),
+            universe
+                .galaxies
+                .iter()
+                .map(|&(x, y)| (x as u64, y as u64))
+                .collect::<Vec<(u64, u64)>>()
+        );
+    }
+
+    #[test]
+    fn test_distance_sum_overflow() {
+        let universe = example();
+        assert!(universe.distance_sum(u64::MAX / 4).is_err());
+        assert!(universe.distance_sum(u64::MAX).is_err());
+        assert_eq!(
+            axis_distance_sum(vec![0, u64::MAX, u64::MAX]),
+            Err(AnswerError::Overflow("distance sum".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_distance_sum_matches_pairs() {
+        let universe = example();
+        for factor in [1, 2, 7, 1_000_000] {
+            assert_eq!(
+                universe.distance_sum(factor).unwrap().value(),
+                naive_distance_sum(&universe.expanded(factor).unwrap())
+            );
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::io;
+
+use aoc23::{
+    answer::{Answer, AnswerError},
+    input, nonogram,
+};
+
+#[derive(Debug, PartialEq)]
+struct Record {
+    springs: String,
+    groups: Vec<usize>,
+}
+
+impl Record {
+    fn new(line: &str) -> Record {
+        let (springs, groups) = line.split_once(' ').unwrap();
+        Record {
+            springs: springs.to_string(),
+            groups: groups
+                .split(',')
+                .map(|g| g.parse::<usize>().unwrap())
+                .collect::<Vec<usize>>(),
+        }
+    }
+
+    fn unfold(&self, times: usize) -> Record {
+        Record {
+            springs: vec![self.springs.as_str(); times].join("?"),
+            groups: self.groups.repeat(times),
+        }
+    }
+
+    fn arrangements(&self) -> Result<Answer, AnswerError> {
+        nonogram::count_arrangements(self.springs.as_bytes(), &self.groups)
+            .map(Answer::new)
+            .ok_or_else(|| AnswerError::Overflow(format!("arrangements of {}", self.springs)))
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day12");
+
+    let records = input::lines("./bin/day12/input")?
+        .map(|l| l.map(|l| Record::new(&l)))
+        .collect::<io::Result<Vec<Record>>>()?;
+
+    println!("records {}", records.len());
+
+    let sum = records.iter().try_fold(Answer::ZERO, |sum, r| {
+        sum.checked_add(r.arrangements()?, "arrangements")
+    })?;
+    println!("arrangements {}", sum);
+
+    let unfolded_sum = records.iter().try_fold(Answer::ZERO, |sum, r| {
+        sum.checked_add(r.unfold(5).arrangements()?, "unfolded arrangements")
+    })?;
+    println!("unfolded arrangements {}", unfolded_sum);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ???.### 1,1,3
+    // .??..??...?##. 1,1,3
+    // ?#?#?#?#?#?#?#? 1,3,1,6
+    // ????.#...#... 4,1,1
+    // ????.######..#####. 1,6,5
+    // ?###???????? 3,2,1
+    //
+    // 21 arrangements, 525152 when unfolded
+
+    const EXAMPLE: [&str; 6] = [
+        "???.### 1,1,3",
+        ".??..??...?##. 1,1,3",
+        "?#?#?#?#?#?#?#? 1,3,1,6",
+        "????.#...#... 4,1,1",
+        "????.######..#####. 1,6,5",
+        "?###???????? 3,2,1",
+    ];
+
+    #[test]
+    fn test_load_record() {
+        let record = Record::new("?#?#?#?#?#?#?#? 1,3,1,6");
+        assert_eq!(record.springs, "?#?#?#?#?#?#?#?");
+        assert_eq!(record.groups, vec![1, 3, 1, 6]);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let record = Record::new(".# 1").unfold(5);
+        assert_eq!(record, Record::new(".#?.#?.#?.#?.# 1,1,1,1,1"));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let records = EXAMPLE
+            .iter()
+            .map(|l| Record::new(l))
+            .collect::<Vec<Record>>();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.arrangements().unwrap().value())
+                .collect::<Vec<u64>>(),
+            vec![1, 4, 1, 1, 4, 10]
+        );
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.arrangements().unwrap().value())
+                .sum::<u64>(),
+            21
+        );
+    }
+
+    #[test]
+    fn test_unfolded_arrangements() {
+        let records = EXAMPLE
+            .iter()
+            .map(|l| Record::new(l))
+            .collect::<Vec<Record>>();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.unfold(5).arrangements().unwrap().value())
+                .collect::<Vec<u64>>(),
+            vec![1, 16384, 1, 16, 2500, 506250]
+        );
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.unfold(5).arrangements().unwrap().value())
+                .sum::<u64>(),
+            525152
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::io;
+
+use aoc23::{answer::Answer, grid::Grid, input};
+
+/// Rows and columns of a pattern encoded as bitmasks, with `#` as a set bit.
+#[derive(Debug)]
+struct Pattern {
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+impl Pattern {
+    /// Fails if the rows differ in length, or there are more than 64 rows or
+    /// columns to fit in a bitmask.
+    fn new(lines: &[String]) -> io::Result<Pattern> {
+        let grid = Grid::from_lines(lines);
+        grid.ensure_rectangular()?;
+        if grid.width() > 64 || grid.height() > 64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "pattern of {}x{} is larger than 64x64",
+                    grid.width(),
+                    grid.height()
+                ),
+            ));
+        }
+
+        let mut rows = vec![0u64; grid.height()];
+        let mut cols = vec![0u64; grid.width()];
+        for (y, row) in grid.rows().iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                if *c == '#' {
+                    rows[y] |= 1 << x;
+                    cols[x] |= 1 << y;
+                }
+            }
+        }
+
+        Ok(Pattern { rows, cols })
+    }
+
+    /// Number of lines before the mirror, where the reflected lines differ in
+    /// exactly `smudges` cells.
+    fn find_mirror(lines: &[u64], smudges: u32) -> Option<usize> {
+        (1..lines.len()).find(|&split| {
+            let diff = lines[..split]
+                .iter()
+                .rev()
+                .zip(&lines[split..])
+                .map(|(a, b)| (a ^ b).count_ones())
+                .sum::<u32>();
+            diff == smudges
+        })
+    }
+
+    /// `None` if the pattern has no mirror with that many smudges.
+    fn summarize(&self, smudges: u32) -> Option<usize> {
+        if let Some(cols) = Pattern::find_mirror(&self.cols, smudges) {
+            return Some(cols);
+        }
+
+        Pattern::find_mirror(&self.rows, smudges).map(|rows| rows * 100)
+    }
+}
+
+fn summaries(patterns: &[Pattern], smudges: u32) -> io::Result<Vec<usize>> {
+    patterns
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            p.summarize(smudges).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("no mirror with {} smudges in pattern {}", smudges, i + 1),
+                )
+            })
+        })
+        .collect()
+}
+
+fn main() -> io::Result<()> {
+    println!("day13");
+
+    let patterns = input::blocks("./bin/day13/input")?
+        .iter()
+        .map(|b| Pattern::new(b))
+        .collect::<io::Result<Vec<Pattern>>>()?;
+
+    println!("patterns {}", patterns.len());
+
+    let sum = Answer::sum(summaries(&patterns, 0)?, "summary")?;
+    println!("summary {}", sum);
+
+    let smudged_sum = Answer::sum(summaries(&patterns, 1)?, "smudged summary")?;
+    println!("smudged summary {}", smudged_sum);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // #.##..##.
+    // ..#.##.#.
+    // ##......#
+    // ##......#
+    // ..#.##.#.
+    // ..##..##.
+    // #.#.##.#.
+    //
+    // #...##..#
+    // #....#..#
+    // ..##..###
+    // #####.##.
+    // #####.##.
+    // ..##..###
+    // #....#..#
+    //
+    // Vertical mirror after column 5 in the first, horizontal after row 4 in
+    // the second, summary 405. With smudges fixed rows 3 and 1, summary 400.
+
+    const EXAMPLE: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+
+    fn example() -> Vec<Pattern> {
+        input::read_blocks(EXAMPLE.as_bytes())
+            .unwrap()
+            .iter()
+            .map(|b| Pattern::new(b))
+            .collect::<io::Result<Vec<Pattern>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_load() {
+        let patterns = example();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].rows.len(), 7);
+        assert_eq!(patterns[0].cols.len(), 9);
+        assert_eq!(patterns[0].rows[0], 0b011001101);
+        assert_eq!(patterns[0].cols[0], 0b1001101);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let lines = |v: &[&str]| v.iter().map(|l| l.to_string()).collect::<Vec<String>>();
+        assert!(Pattern::new(&lines(&["#.#", "#.", "#.#"])).is_err());
+        assert!(Pattern::new(&lines(&["#.##.#", "#.##.#.#"])).is_err());
+        assert!(Pattern::new(&lines(&[&"#.".repeat(32), &".#".repeat(32)])).is_ok());
+        assert!(Pattern::new(&lines(&[&"#".repeat(65), &"#".repeat(65)])).is_err());
+        assert!(Pattern::new(&lines(&["#."; 65])).is_err());
+    }
+
+    #[test]
+    fn test_find_mirror() {
+        let patterns = example();
+        assert_eq!(Pattern::find_mirror(&patterns[0].cols, 0), Some(5));
+        assert_eq!(Pattern::find_mirror(&patterns[0].rows, 0), None);
+        assert_eq!(Pattern::find_mirror(&patterns[1].rows, 0), Some(4));
+        assert_eq!(Pattern::find_mirror(&patterns[0].rows, 1), Some(3));
+        assert_eq!(Pattern::find_mirror(&patterns[1].rows, 1), Some(1));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let patterns = example();
+        assert_eq!(summaries(&patterns, 0).unwrap().iter().sum::<usize>(), 405);
+        assert_eq!(summaries(&patterns, 1).unwrap().iter().sum::<usize>(), 400);
+    }
+
+    #[test]
+    fn test_no_mirror() {
+        let pattern = Pattern::new(&["#..".to_string(), "..#".to_string()]).unwrap();
+        assert_eq!(pattern.summarize(0), None);
+        assert_eq!(
+            summaries(
+                &example()
+                    .into_iter()
+                    .chain([pattern])
+                    .collect::<Vec<Pattern>>(),
+                0
+            )
+            .unwrap_err()
+            .to_string(),
+            "no mirror with 0 smudges in pattern 3"
+        );
+    }
+}
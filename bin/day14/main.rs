@@ -0,0 +1,198 @@
+use std::io;
+
+use aoc23::{
+    answer::{Answer, AnswerError},
+    cycle,
+    grid::{Direction, Grid},
+    input,
+};
+
+const SPIN: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Platform {
+    grid: Grid<char>,
+}
+
+impl Platform {
+    fn new(grid: Grid<char>) -> Platform {
+        Platform { grid }
+    }
+
+    /// Position of the `i`th cell along `lane`, counted from the edge the
+    /// rocks roll towards.
+    fn lane_position(&self, dir: Direction, lane: usize, i: usize) -> (usize, usize) {
+        match dir {
+            Direction::North => (lane, i),
+            Direction::South => (lane, self.grid.height() - 1 - i),
+            Direction::West => (i, lane),
+            Direction::East => (self.grid.width() - 1 - i, lane),
+        }
+    }
+
+    fn tilt(&mut self, dir: Direction) {
+        let (lanes, len) = match dir {
+            Direction::North | Direction::South => (self.grid.width(), self.grid.height()),
+            Direction::East | Direction::West => (self.grid.height(), self.grid.width()),
+        };
+
+        for lane in 0..lanes {
+            let mut free = 0;
+            for i in 0..len {
+                let (x, y) = self.lane_position(dir, lane, i);
+                match self.grid.get(x, y) {
+                    '#' => free = i + 1,
+                    'O' => {
+                        self.grid.set(x, y, '.');
+                        let (fx, fy) = self.lane_position(dir, lane, free);
+                        self.grid.set(fx, fy, 'O');
+                        free += 1;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn spin(&self) -> Platform {
+        let mut platform = self.clone();
+        for dir in SPIN {
+            platform.tilt(dir);
+        }
+
+        platform
+    }
+
+    fn north_load(&self) -> Result<Answer, AnswerError> {
+        Answer::sum(
+            self.grid
+                .positions()
+                .filter(|&(x, y)| *self.grid.get(x, y) == 'O')
+                .map(|(_, y)| self.grid.height() - y),
+            "north load",
+        )
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day14");
+
+    let lines = input::lines("./bin/day14/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = Grid::from_lines(&lines);
+    grid.ensure_rectangular()?;
+
+    let platform = Platform::new(grid);
+
+    let mut tilted = platform.clone();
+    tilted.tilt(Direction::North);
+    println!("north load {}", tilted.north_load()?);
+
+    let spun = cycle::fast_forward(platform, 1_000_000_000, Platform::spin);
+    println!("north load after spinning {}", spun.north_load()?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // O....#....
+    // O.OO#....#
+    // .....##...
+    // OO.#O....O
+    // .O.....O#.
+    // O.#..O.#.#
+    // ..O..#O..O
+    // .......O..
+    // #....###..
+    // #OO..#....
+    //
+    // Load after tilting north is 136, after 1000000000 spins 64
+
+    fn example() -> Platform {
+        Platform::new(Grid::from_lines(&[
+            "O....#....",
+            "O.OO#....#",
+            ".....##...",
+            "OO.#O....O",
+            ".O.....O#.",
+            "O.#..O.#.#",
+            "..O..#O..O",
+            ".......O..",
+            "#....###..",
+            "#OO..#....",
+        ]))
+    }
+
+    #[test]
+    fn test_tilt_north() {
+        let mut platform = example();
+        platform.tilt(Direction::North);
+        assert_eq!(
+            platform.grid,
+            Grid::from_lines(&[
+                "OOOO.#.O..",
+                "OO..#....#",
+                "OO..O##..O",
+                "O..#.OO...",
+                "........#.",
+                "..#....#.#",
+                "..O..#.O.O",
+                "..O.......",
+                "#....###..",
+                "#....#....",
+            ])
+        );
+        assert_eq!(platform.north_load(), Ok(Answer::new(136)));
+    }
+
+    #[test]
+    fn test_spin() {
+        let platform = example().spin();
+        assert_eq!(
+            platform.grid,
+            Grid::from_lines(&[
+                ".....#....",
+                "....#...O#",
+                "...OO##...",
+                ".OO#......",
+                ".....OOO#.",
+                ".O#...O#.#",
+                "....O#....",
+                "......OOOO",
+                "#...O###..",
+                "#..OO#....",
+            ])
+        );
+
+        let platform = platform.spin().spin();
+        assert_eq!(
+            platform.grid,
+            Grid::from_lines(&[
+                ".....#....",
+                "....#...O#",
+                ".....##...",
+                "..O#......",
+                ".....OOO#.",
+                ".O#...O#.#",
+                "....O#...O",
+                ".......OOO",
+                "#...O###.O",
+                "#.OOO#...O",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_spin_billion() {
+        let platform = cycle::fast_forward(example(), 1_000_000_000, Platform::spin);
+        assert_eq!(platform.north_load(), Ok(Answer::new(64)));
+    }
+}
@@ -0,0 +1,167 @@
+use std::io;
+
+use aoc23::{
+    answer::{Answer, AnswerError},
+    input,
+};
+
+fn hash(s: &str) -> u8 {
+    s.bytes()
+        .fold(0u8, |acc, c| acc.wrapping_add(c).wrapping_mul(17))
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Remove(String),
+    Insert(String, u8),
+}
+
+impl Step {
+    fn new(s: &str) -> Step {
+        match s.split_once('=') {
+            Some((label, focal)) => Step::Insert(label.to_string(), focal.parse::<u8>().unwrap()),
+            None => Step::Remove(s.trim_end_matches('-').to_string()),
+        }
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            Step::Remove(label) => label,
+            Step::Insert(label, _) => label,
+        }
+    }
+}
+
+/// 256 boxes of labelled lenses, each kept in insertion order.
+struct Boxes {
+    boxes: Vec<Vec<(String, u8)>>,
+}
+
+impl Boxes {
+    fn new() -> Boxes {
+        Boxes {
+            boxes: vec![Vec::new(); 256],
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        let lenses = &mut self.boxes[hash(step.label()) as usize];
+        let slot = lenses.iter().position(|(l, _)| l == step.label());
+
+        match (step, slot) {
+            (Step::Remove(_), Some(i)) => {
+                lenses.remove(i);
+            }
+            (Step::Remove(_), None) => (),
+            (Step::Insert(_, focal), Some(i)) => lenses[i].1 = *focal,
+            (Step::Insert(label, focal), None) => lenses.push((label.clone(), *focal)),
+        }
+    }
+
+    fn focusing_power(&self) -> Result<Answer, AnswerError> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(b, lenses)| {
+                lenses.iter().enumerate().map(move |(slot, (_, focal))| {
+                    Answer::product([b + 1, slot + 1, *focal as usize], "focusing power")
+                })
+            })
+            .try_fold(Answer::ZERO, |sum, power| {
+                sum.checked_add(power?, "focusing power")
+            })
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day15");
+
+    let mut hash_sum = Answer::ZERO;
+    let mut boxes = Boxes::new();
+    for f in input::fields("./bin/day15/input", b',')? {
+        let field = f?;
+
+        hash_sum = hash_sum.checked_add(u32::from(hash(&field)), "hash sum")?;
+        boxes.apply(&Step::new(&field));
+    }
+
+    println!("hash sum {}", hash_sum);
+    println!("focusing power {}", boxes.focusing_power()?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+    //
+    // Hash sum 1320, focusing power 145
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+
+    fn example() -> Vec<String> {
+        input::read_fields(EXAMPLE.as_bytes(), b',')
+            .collect::<io::Result<Vec<String>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash("HASH"), 52);
+        assert_eq!(hash("rn=1"), 30);
+        assert_eq!(hash("rn"), 0);
+        assert_eq!(hash("qp"), 1);
+        assert_eq!(example().iter().map(|s| hash(s) as u32).sum::<u32>(), 1320);
+    }
+
+    #[test]
+    fn test_load_step() {
+        assert_eq!(Step::new("rn=1"), Step::Insert("rn".to_string(), 1));
+        assert_eq!(Step::new("cm-"), Step::Remove("cm".to_string()));
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let mut boxes = Boxes::new();
+        for step in ["rn=1", "cm=2", "rn=4"] {
+            boxes.apply(&Step::new(step));
+        }
+        assert_eq!(
+            boxes.boxes[0],
+            vec![("rn".to_string(), 4), ("cm".to_string(), 2)]
+        );
+
+        for step in ["rn-", "rn=5"] {
+            boxes.apply(&Step::new(step));
+        }
+        assert_eq!(
+            boxes.boxes[0],
+            vec![("cm".to_string(), 2), ("rn".to_string(), 5)]
+        );
+    }
+
+    #[test]
+    fn test_focusing_power() {
+        let mut boxes = Boxes::new();
+        for step in example() {
+            boxes.apply(&Step::new(&step));
+        }
+
+        assert_eq!(
+            boxes.boxes[0],
+            vec![("rn".to_string(), 1), ("cm".to_string(), 2)]
+        );
+        assert!(boxes.boxes[1].is_empty());
+        assert_eq!(
+            boxes.boxes[3],
+            vec![
+                ("ot".to_string(), 7),
+                ("ab".to_string(), 5),
+                ("pc".to_string(), 6)
+            ]
+        );
+        assert_eq!(boxes.focusing_power(), Ok(Answer::new(145)));
+    }
+}
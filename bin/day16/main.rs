@@ -0,0 +1,257 @@
+use std::{io, thread};
+
+use aoc23::{
+    grid::{Direction, Grid},
+    input,
+};
+
+/// Cells a beam has passed through, with one bit per direction of travel.
+struct Beams {
+    visited: Grid<u8>,
+}
+
+impl Beams {
+    fn energized(&self) -> usize {
+        self.visited
+            .positions()
+            .filter(|&(x, y)| *self.visited.get(x, y) != 0)
+            .count()
+    }
+
+    /// Draws the beams over the contraption: mirrors and splitters as they
+    /// are, empty cells with the beam direction or the number of beams.
+    fn render(&self, grid: &Grid<char>) -> String {
+        let mut out = String::new();
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let bits = *self.visited.get(x, y);
+                let c = match (*grid.get(x, y), bits.count_ones()) {
+                    ('.', 0) => '.',
+                    ('.', 1) => match Direction::ALL[bits.trailing_zeros() as usize] {
+                        Direction::North => '^',
+                        Direction::East => '>',
+                        Direction::South => 'v',
+                        Direction::West => '<',
+                    },
+                    ('.', n) => char::from_digit(n, 10).unwrap(),
+                    (c, _) => c,
+                };
+                out.push(c);
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    fn render_energized(&self) -> String {
+        let mut out = String::new();
+        for row in self.visited.rows() {
+            out.extend(row.iter().map(|bits| if *bits != 0 { '#' } else { '.' }));
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+struct Contraption {
+    grid: Grid<char>,
+}
+
+impl Contraption {
+    fn new(grid: Grid<char>) -> Contraption {
+        Contraption { grid }
+    }
+
+    fn deflect(tile: char, dir: Direction) -> (Direction, Option<Direction>) {
+        match (tile, dir) {
+            ('/', Direction::North | Direction::South) => (dir.turn_right(), None),
+            ('/', Direction::East | Direction::West) => (dir.turn_left(), None),
+            ('\\', Direction::North | Direction::South) => (dir.turn_left(), None),
+            ('\\', Direction::East | Direction::West) => (dir.turn_right(), None),
+            ('|', Direction::East | Direction::West) => (Direction::North, Some(Direction::South)),
+            ('-', Direction::North | Direction::South) => (Direction::East, Some(Direction::West)),
+            _ => (dir, None),
+        }
+    }
+
+    fn trace(&self, start: (usize, usize), dir: Direction) -> Beams {
+        let mut visited = Grid::filled(self.grid.width(), self.grid.height(), 0u8);
+        let mut queue = vec![(start, dir)];
+
+        while let Some(((x, y), dir)) = queue.pop() {
+            let bit = 1 << dir as u8;
+            if visited.get(x, y) & bit != 0 {
+                continue;
+            }
+            visited.set(x, y, visited.get(x, y) | bit);
+
+            let (a, b) = Contraption::deflect(*self.grid.get(x, y), dir);
+            for next in [Some(a), b].into_iter().flatten() {
+                if let Some(pos) = self.grid.step(x, y, next) {
+                    queue.push((pos, next));
+                }
+            }
+        }
+
+        Beams { visited }
+    }
+
+    fn edge_starts(&self) -> Vec<((usize, usize), Direction)> {
+        let (w, h) = (self.grid.width(), self.grid.height());
+        let mut starts = Vec::new();
+        for x in 0..w {
+            starts.push(((x, 0), Direction::South));
+            starts.push(((x, h - 1), Direction::North));
+        }
+        for y in 0..h {
+            starts.push(((0, y), Direction::East));
+            starts.push(((w - 1, y), Direction::West));
+        }
+
+        starts
+    }
+
+    fn max_energized(&self) -> usize {
+        let starts = self.edge_starts();
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = starts.len().div_ceil(workers).max(1);
+
+        thread::scope(|s| {
+            let handles = starts
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(pos, dir)| self.trace(*pos, *dir).energized())
+                            .max()
+                            .unwrap_or_default()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .max()
+                .unwrap_or_default()
+        })
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day16");
+
+    let lines = input::lines("./bin/day16/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = Grid::from_lines(&lines);
+    grid.ensure_rectangular()?;
+
+    let contraption = Contraption::new(grid);
+    let beams = contraption.trace((0, 0), Direction::East);
+    print!("{}", beams.render(&contraption.grid));
+    print!("{}", beams.render_energized());
+
+    println!("energized {}", beams.energized());
+    println!("max energized {}", contraption.max_energized());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // .|...\....
+    // |.-.\.....
+    // .....|-...
+    // ........|.
+    // ..........
+    // .........\
+    // ..../.\\..
+    // .-.-/..|..
+    // .|....-|.\
+    // ..//.|....
+    //
+    // 46 energized from the top left, 51 at most from the edges
+
+    fn example() -> Contraption {
+        Contraption::new(Grid::from_lines(&[
+            r".|...\....",
+            r"|.-.\.....",
+            r".....|-...",
+            r"........|.",
+            r"..........",
+            r".........\",
+            r"..../.\\..",
+            r".-.-/..|..",
+            r".|....-|.\",
+            r"..//.|....",
+        ]))
+    }
+
+    #[test]
+    fn test_deflect() {
+        assert_eq!(
+            Contraption::deflect('/', Direction::East),
+            (Direction::North, None)
+        );
+        assert_eq!(
+            Contraption::deflect('/', Direction::South),
+            (Direction::West, None)
+        );
+        assert_eq!(
+            Contraption::deflect('\\', Direction::East),
+            (Direction::South, None)
+        );
+        assert_eq!(
+            Contraption::deflect('\\', Direction::North),
+            (Direction::West, None)
+        );
+        assert_eq!(
+            Contraption::deflect('|', Direction::North),
+            (Direction::North, None)
+        );
+        assert_eq!(
+            Contraption::deflect('-', Direction::South),
+            (Direction::East, Some(Direction::West))
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let beams = example().trace((0, 0), Direction::East);
+        assert_eq!(
+            beams.render_energized(),
+            "######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..
+"
+        );
+        assert_eq!(beams.energized(), 46);
+    }
+
+    #[test]
+    fn test_render() {
+        let contraption = Contraption::new(Grid::from_lines(&[r".\.", r"...", r".-."]));
+        let beams = contraption.trace((0, 0), Direction::East);
+        assert_eq!(beams.render(&contraption.grid), ">\\.\n.v.\n<->\n");
+    }
+
+    #[test]
+    fn test_max_energized() {
+        let contraption = example();
+        assert_eq!(contraption.edge_starts().len(), 40);
+        assert_eq!(contraption.trace((3, 0), Direction::South).energized(), 51);
+        assert_eq!(contraption.max_energized(), 51);
+    }
+}
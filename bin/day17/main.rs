@@ -0,0 +1,217 @@
+use std::io;
+
+use aoc23::{
+    grid::{Direction, Grid},
+    input, pathfinding,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State {
+    pos: (usize, usize),
+    dir: Direction,
+    run: usize,
+}
+
+/// Limits on how far a crucible moves in a straight line: at least `min_run`
+/// blocks before it can turn or stop, and at most `max_run` blocks.
+struct Crucible {
+    min_run: usize,
+    max_run: usize,
+}
+
+impl Crucible {
+    fn neighbours(&self, grid: &Grid<u32>, state: &State) -> Vec<(State, u32)> {
+        [state.dir, state.dir.turn_left(), state.dir.turn_right()]
+            .iter()
+            .filter(|dir| {
+                if **dir == state.dir {
+                    state.run < self.max_run
+                } else {
+                    state.run >= self.min_run
+                }
+            })
+            .filter_map(|dir| {
+                let (x, y) = grid.step(state.pos.0, state.pos.1, *dir)?;
+                let run = if *dir == state.dir { state.run + 1 } else { 1 };
+                Some((
+                    State {
+                        pos: (x, y),
+                        dir: *dir,
+                        run,
+                    },
+                    *grid.get(x, y),
+                ))
+            })
+            .collect::<Vec<(State, u32)>>()
+    }
+
+    fn least_heat_loss(&self, grid: &Grid<u32>) -> Option<u32> {
+        let goal = (grid.width() - 1, grid.height() - 1);
+        let starts = [Direction::East, Direction::South].map(|dir| State {
+            pos: (0, 0),
+            dir,
+            run: 0,
+        });
+
+        pathfinding::astar(
+            starts,
+            |s| self.neighbours(grid, s),
+            |s| (goal.0 - s.pos.0 + goal.1 - s.pos.1) as u32,
+            |s| s.pos == goal && s.run >= self.min_run,
+        )
+        .map(|(cost, _)| cost)
+    }
+}
+
+fn load(lines: &[String]) -> Grid<u32> {
+    Grid::from_lines(lines).map(|c| c.to_digit(10).unwrap())
+}
+
+fn main() -> io::Result<()> {
+    println!("day17");
+
+    let lines = input::lines("./bin/day17/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = load(&lines);
+    grid.ensure_rectangular()?;
+
+    let crucible = Crucible {
+        min_run: 1,
+        max_run: 3,
+    };
+    println!(
+        "least heat loss {}",
+        crucible.least_heat_loss(&grid).unwrap()
+    );
+
+    let ultra_crucible = Crucible {
+        min_run: 4,
+        max_run: 10,
+    };
+    println!(
+        "least heat loss ultra {}",
+        ultra_crucible.least_heat_loss(&grid).unwrap()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2413432311323
+    // 3215453535623
+    // 3255245654254
+    // 3446585845452
+    // 4546657867536
+    // 1438598798454
+    // 4457876987766
+    // 3637877979653
+    // 4654967986887
+    // 4564679986453
+    // 1224686865563
+    // 2546548887735
+    // 4322674655533
+    //
+    // Least heat loss 102, 94 with an ultra crucible
+
+    const CRUCIBLE: Crucible = Crucible {
+        min_run: 1,
+        max_run: 3,
+    };
+
+    const ULTRA_CRUCIBLE: Crucible = Crucible {
+        min_run: 4,
+        max_run: 10,
+    };
+
+    fn grid(lines: &[&str]) -> Grid<u32> {
+        load(&lines.iter().map(|l| l.to_string()).collect::<Vec<String>>())
+    }
+
+    fn example() -> Grid<u32> {
+        grid(&[
+            "2413432311323",
+            "3215453535623",
+            "3255245654254",
+            "3446585845452",
+            "4546657867536",
+            "1438598798454",
+            "4457876987766",
+            "3637877979653",
+            "4654967986887",
+            "4564679986453",
+            "1224686865563",
+            "2546548887735",
+            "4322674655533",
+        ])
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid(&["123", "456", "789"]);
+        let state = State {
+            pos: (1, 1),
+            dir: Direction::East,
+            run: 3,
+        };
+
+        let next = CRUCIBLE.neighbours(&grid, &state);
+        assert_eq!(next.len(), 2);
+        assert!(next.contains(&(
+            State {
+                pos: (1, 0),
+                dir: Direction::North,
+                run: 1
+            },
+            2
+        )));
+
+        let next = ULTRA_CRUCIBLE.neighbours(&grid, &state);
+        assert_eq!(
+            next,
+            vec![(
+                State {
+                    pos: (2, 1),
+                    dir: Direction::East,
+                    run: 4
+                },
+                6
+            )]
+        );
+    }
+
+    #[test]
+    fn test_small_straight_limit() {
+        // straight along the top is 4 blocks, so it has to dip into the 9s
+        let grid = grid(&["11111", "99991"]);
+        assert_eq!(CRUCIBLE.least_heat_loss(&grid), Some(13));
+    }
+
+    #[test]
+    fn test_small_minimum_run() {
+        // only the ultra crucible can follow the 1s around the edge
+        let grid = grid(&["11111", "19999", "19999", "19999", "11111"]);
+        assert_eq!(CRUCIBLE.least_heat_loss(&grid), Some(16));
+        assert_eq!(ULTRA_CRUCIBLE.least_heat_loss(&grid), Some(8));
+    }
+
+    #[test]
+    fn test_least_heat_loss() {
+        assert_eq!(CRUCIBLE.least_heat_loss(&example()), Some(102));
+        assert_eq!(ULTRA_CRUCIBLE.least_heat_loss(&example()), Some(94));
+    }
+
+    #[test]
+    fn test_least_heat_loss_ultra_unfair() {
+        let grid = grid(&[
+            "111111111111",
+            "999999999991",
+            "999999999991",
+            "999999999991",
+            "999999999991",
+        ]);
+        assert_eq!(ULTRA_CRUCIBLE.least_heat_loss(&grid), Some(71));
+    }
+}
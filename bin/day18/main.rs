@@ -0,0 +1,204 @@
+use std::io;
+
+use aoc23::{geometry, grid::Direction, input};
+
+#[derive(Debug, PartialEq)]
+struct Instruction {
+    dir: Direction,
+    len: i64,
+}
+
+impl Instruction {
+    fn new(line: &str) -> Instruction {
+        let parts = line.split(' ').collect::<Vec<&str>>();
+        let dir = match parts[0] {
+            "U" => Direction::North,
+            "R" => Direction::East,
+            "D" => Direction::South,
+            "L" => Direction::West,
+            d => panic!("unknown direction {}", d),
+        };
+
+        Instruction {
+            dir,
+            len: parts[1].parse::<i64>().unwrap(),
+        }
+    }
+
+    /// Instruction hidden in the colour code, `(#70c710)` being 0x70c71
+    /// blocks in direction 0 (right).
+    fn from_hex(line: &str) -> Instruction {
+        let hex = line
+            .split(' ')
+            .nth(2)
+            .unwrap()
+            .trim_start_matches("(#")
+            .trim_end_matches(')');
+        let dir = match &hex[5..] {
+            "0" => Direction::East,
+            "1" => Direction::South,
+            "2" => Direction::West,
+            "3" => Direction::North,
+            d => panic!("unknown direction {}", d),
+        };
+
+        Instruction {
+            dir,
+            len: i64::from_str_radix(&hex[..5], 16).unwrap(),
+        }
+    }
+}
+
+/// Corners of the trench, or `None` if a coordinate overflows.
+fn trench(instructions: &[Instruction]) -> Option<Vec<(i64, i64)>> {
+    let (mut x, mut y) = (0i64, 0i64);
+    let mut corners = Vec::with_capacity(instructions.len());
+    for i in instructions {
+        let (dx, dy) = i.dir.delta();
+        x = x.checked_add(dx.checked_mul(i.len)?)?;
+        y = y.checked_add(dy.checked_mul(i.len)?)?;
+        corners.push((x, y));
+    }
+
+    Some(corners)
+}
+
+fn lagoon_size(instructions: &[Instruction]) -> io::Result<i64> {
+    trench(instructions)
+        .and_then(|corners| geometry::rectilinear_filled_area(&corners))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "lagoon size overflows"))
+}
+
+fn main() -> io::Result<()> {
+    println!("day18");
+
+    let lines = input::lines("./bin/day18/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let instructions = lines
+        .iter()
+        .map(|l| Instruction::new(l))
+        .collect::<Vec<Instruction>>();
+    println!("lagoon {}", lagoon_size(&instructions)?);
+
+    let hex_instructions = lines
+        .iter()
+        .map(|l| Instruction::from_hex(l))
+        .collect::<Vec<Instruction>>();
+    println!("hex lagoon {}", lagoon_size(&hex_instructions)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // R 6 (#70c710)
+    // D 5 (#0dc571)
+    // L 2 (#5713f0)
+    // D 2 (#d2c081)
+    // R 2 (#59c680)
+    // D 2 (#411b91)
+    // L 5 (#8ceee2)
+    // U 2 (#caa173)
+    // L 1 (#1b58a2)
+    // U 2 (#caa171)
+    // R 2 (#7807d2)
+    // U 3 (#a77fa3)
+    // L 2 (#015232)
+    // U 2 (#7a21e3)
+    //
+    // Lagoon holds 62, 952408144115 with the hex instructions
+
+    const EXAMPLE: [&str; 14] = [
+        "R 6 (#70c710)",
+        "D 5 (#0dc571)",
+        "L 2 (#5713f0)",
+        "D 2 (#d2c081)",
+        "R 2 (#59c680)",
+        "D 2 (#411b91)",
+        "L 5 (#8ceee2)",
+        "U 2 (#caa173)",
+        "L 1 (#1b58a2)",
+        "U 2 (#caa171)",
+        "R 2 (#7807d2)",
+        "U 3 (#a77fa3)",
+        "L 2 (#015232)",
+        "U 2 (#7a21e3)",
+    ];
+
+    #[test]
+    fn test_load_instruction() {
+        assert_eq!(
+            Instruction::new("R 6 (#70c710)"),
+            Instruction {
+                dir: Direction::East,
+                len: 6
+            }
+        );
+        assert_eq!(
+            Instruction::from_hex("R 6 (#70c710)"),
+            Instruction {
+                dir: Direction::East,
+                len: 461937
+            }
+        );
+        assert_eq!(
+            Instruction::from_hex("U 2 (#caa173)"),
+            Instruction {
+                dir: Direction::North,
+                len: 829975
+            }
+        );
+    }
+
+    #[test]
+    fn test_trench() {
+        let instructions = EXAMPLE[..3]
+            .iter()
+            .map(|l| Instruction::new(l))
+            .collect::<Vec<Instruction>>();
+        assert_eq!(trench(&instructions), Some(vec![(6, 0), (6, 5), (4, 5)]));
+    }
+
+    #[test]
+    fn test_lagoon_size() {
+        let instructions = EXAMPLE
+            .iter()
+            .map(|l| Instruction::new(l))
+            .collect::<Vec<Instruction>>();
+        assert_eq!(lagoon_size(&instructions).unwrap(), 62);
+    }
+
+    #[test]
+    fn test_lagoon_size_hex() {
+        let instructions = EXAMPLE
+            .iter()
+            .map(|l| Instruction::from_hex(l))
+            .collect::<Vec<Instruction>>();
+        assert_eq!(lagoon_size(&instructions).unwrap(), 952408144115);
+    }
+
+    #[test]
+    fn test_lagoon_size_overflow() {
+        let huge = |dir| Instruction {
+            dir,
+            len: i64::MAX / 2,
+        };
+        let square = [
+            huge(Direction::East),
+            huge(Direction::South),
+            huge(Direction::West),
+            huge(Direction::North),
+        ];
+        assert!(lagoon_size(&square).is_err());
+
+        let far = [
+            huge(Direction::East),
+            huge(Direction::East),
+            huge(Direction::East),
+        ];
+        assert_eq!(trench(&far), None);
+        assert!(lagoon_size(&far).is_err());
+    }
+}
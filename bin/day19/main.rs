@@ -0,0 +1,413 @@
+use std::{collections::HashMap, io};
+
+use aoc23::{
+    answer::{Answer, AnswerError},
+    input,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl Category {
+    fn new(s: &str) -> Category {
+        match s {
+            "x" => Category::X,
+            "m" => Category::M,
+            "a" => Category::A,
+            "s" => Category::S,
+            c => panic!("unknown category {}", c),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Condition {
+    Less(Category, u64),
+    Greater(Category, u64),
+}
+
+#[derive(Debug, PartialEq)]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl Target {
+    fn new(s: &str) -> Target {
+        match s {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            name => Target::Workflow(name.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Rule {
+    condition: Option<Condition>,
+    target: Target,
+}
+
+impl Rule {
+    fn new(s: &str) -> Rule {
+        let Some((condition, target)) = s.split_once(':') else {
+            return Rule {
+                condition: None,
+                target: Target::new(s),
+            };
+        };
+
+        let value = condition[2..].parse::<u64>().unwrap();
+        let category = Category::new(&condition[..1]);
+        let condition = match &condition[1..2] {
+            "<" => Condition::Less(category, value),
+            ">" => Condition::Greater(category, value),
+            op => panic!("unknown operator {}", op),
+        };
+
+        Rule {
+            condition: Some(condition),
+            target: Target::new(target),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Part {
+    ratings: [u64; 4],
+}
+
+impl Part {
+    fn new(line: &str) -> Part {
+        let mut ratings = [0; 4];
+        line.trim_start_matches('{')
+            .trim_end_matches('}')
+            .split(',')
+            .for_each(|r| {
+                let (category, value) = r.split_once('=').unwrap();
+                ratings[Category::new(category) as usize] = value.parse::<u64>().unwrap();
+            });
+
+        Part { ratings }
+    }
+
+    fn rating(&self) -> Result<Answer, AnswerError> {
+        Answer::sum(self.ratings, "part rating")
+    }
+}
+
+/// Half-open rating ranges for each category.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PartRange {
+    ranges: [(u64, u64); 4],
+}
+
+impl PartRange {
+    fn is_empty(&self) -> bool {
+        self.ranges.iter().any(|(lo, hi)| lo >= hi)
+    }
+
+    fn combinations(&self) -> Result<Answer, AnswerError> {
+        Answer::product(
+            self.ranges.iter().map(|(lo, hi)| hi.saturating_sub(*lo)),
+            "combinations",
+        )
+    }
+
+    /// Splits into the parts matching `condition` and the rest.
+    fn split(&self, condition: &Condition) -> (Option<PartRange>, Option<PartRange>) {
+        let (category, cut, below_matches) = match condition {
+            Condition::Less(c, v) => (*c as usize, *v, true),
+            Condition::Greater(c, v) => (*c as usize, v.saturating_add(1), false),
+        };
+
+        let (lo, hi) = self.ranges[category];
+        let mut below = *self;
+        below.ranges[category] = (lo, cut.clamp(lo, hi));
+        let mut above = *self;
+        above.ranges[category] = (cut.clamp(lo, hi), hi);
+
+        let below = Some(below).filter(|r| !r.is_empty());
+        let above = Some(above).filter(|r| !r.is_empty());
+
+        if below_matches {
+            (below, above)
+        } else {
+            (above, below)
+        }
+    }
+}
+
+struct System {
+    workflows: HashMap<String, Vec<Rule>>,
+}
+
+impl System {
+    fn new(lines: &[String]) -> System {
+        let workflows = lines
+            .iter()
+            .map(|l| {
+                let (name, rules) = l.trim_end_matches('}').split_once('{').unwrap();
+                (
+                    name.to_string(),
+                    rules.split(',').map(Rule::new).collect::<Vec<Rule>>(),
+                )
+            })
+            .collect::<HashMap<String, Vec<Rule>>>();
+
+        System { workflows }
+    }
+
+    fn accepts(&self, part: &Part) -> bool {
+        let mut name = "in";
+        loop {
+            let rule = self.workflows[name]
+                .iter()
+                .find(|r| match &r.condition {
+                    None => true,
+                    Some(Condition::Less(c, v)) => part.ratings[*c as usize] < *v,
+                    Some(Condition::Greater(c, v)) => part.ratings[*c as usize] > *v,
+                })
+                .unwrap();
+
+            match &rule.target {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(next) => name = next,
+            }
+        }
+    }
+
+    fn count_accepted(&self, name: &str, range: PartRange) -> Result<Answer, AnswerError> {
+        let mut remaining = Some(range);
+        let mut count = Answer::ZERO;
+
+        for rule in &self.workflows[name] {
+            let Some(current) = remaining else {
+                break;
+            };
+
+            let (matched, rest) = match &rule.condition {
+                None => (Some(current), None),
+                Some(condition) => current.split(condition),
+            };
+            remaining = rest;
+
+            if let Some(matched) = matched {
+                let accepted = match &rule.target {
+                    Target::Accept => matched.combinations()?,
+                    Target::Reject => Answer::ZERO,
+                    Target::Workflow(next) => self.count_accepted(next, matched)?,
+                };
+                count = count.checked_add(accepted, "accepted combinations")?;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day19");
+
+    let blocks = input::blocks("./bin/day19/input")?;
+
+    let system = System::new(&blocks[0]);
+    let parts = blocks[1]
+        .iter()
+        .map(|l| Part::new(l))
+        .collect::<Vec<Part>>();
+
+    println!("workflows {} parts {}", system.workflows.len(), parts.len());
+
+    let rating_sum = parts
+        .iter()
+        .filter(|p| system.accepts(p))
+        .try_fold(Answer::ZERO, |sum, p| {
+            sum.checked_add(p.rating()?, "accepted rating sum")
+        })?;
+    println!("accepted rating sum {}", rating_sum);
+
+    let all = PartRange {
+        ranges: [(1, 4001); 4],
+    };
+    println!(
+        "accepted combinations {}",
+        system.count_accepted("in", all)?
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parts 1, 3 and 5 are accepted with a rating sum of 19114, and
+    // 167409079868000 combinations of ratings from 1 to 4000 are accepted
+
+    const EXAMPLE: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}
+";
+
+    fn example() -> (System, Vec<Part>) {
+        let blocks = input::read_blocks(EXAMPLE.as_bytes()).unwrap();
+        (
+            System::new(&blocks[0]),
+            blocks[1]
+                .iter()
+                .map(|l| Part::new(l))
+                .collect::<Vec<Part>>(),
+        )
+    }
+
+    #[test]
+    fn test_load_rule() {
+        assert_eq!(
+            Rule::new("a<2006:qkq"),
+            Rule {
+                condition: Some(Condition::Less(Category::A, 2006)),
+                target: Target::Workflow("qkq".to_string())
+            }
+        );
+        assert_eq!(
+            Rule::new("m>2090:A"),
+            Rule {
+                condition: Some(Condition::Greater(Category::M, 2090)),
+                target: Target::Accept
+            }
+        );
+        assert_eq!(
+            Rule::new("R"),
+            Rule {
+                condition: None,
+                target: Target::Reject
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_part() {
+        assert_eq!(
+            Part::new("{x=787,m=2655,a=1222,s=2876}"),
+            Part {
+                ratings: [787, 2655, 1222, 2876]
+            }
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let range = PartRange {
+            ranges: [(1, 11); 4],
+        };
+
+        let (matched, rest) = range.split(&Condition::Less(Category::M, 4));
+        assert_eq!(matched.unwrap().ranges[1], (1, 4));
+        assert_eq!(rest.unwrap().ranges[1], (4, 11));
+
+        let (matched, rest) = range.split(&Condition::Greater(Category::S, 4));
+        assert_eq!(matched.unwrap().ranges[3], (5, 11));
+        assert_eq!(rest.unwrap().ranges[3], (1, 5));
+
+        let (matched, rest) = range.split(&Condition::Greater(Category::X, 20));
+        assert_eq!(matched, None);
+        assert_eq!(rest, Some(range));
+    }
+
+    #[test]
+    fn test_accepts() {
+        let (system, parts) = example();
+        assert_eq!(
+            parts
+                .iter()
+                .map(|p| system.accepts(p))
+                .collect::<Vec<bool>>(),
+            vec![true, false, true, false, true]
+        );
+        assert_eq!(
+            parts
+                .iter()
+                .filter(|p| system.accepts(p))
+                .map(|p| p.rating().unwrap().value())
+                .sum::<u64>(),
+            19114
+        );
+    }
+
+    #[test]
+    fn test_count_accepted() {
+        let (system, _) = example();
+        let all = PartRange {
+            ranges: [(1, 4001); 4],
+        };
+        assert_eq!(
+            system.count_accepted("in", all),
+            Ok(Answer::new(167409079868000))
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let (system, _) = example();
+        let all = PartRange {
+            ranges: [(0, u64::MAX); 4],
+        };
+        assert_eq!(
+            system.count_accepted("in", all),
+            Err(AnswerError::Overflow("combinations".to_string()))
+        );
+
+        let part = Part {
+            ratings: [u64::MAX, 1, 0, 0],
+        };
+        assert!(part.rating().is_err());
+    }
+
+    #[test]
+    fn test_count_accepted_matches_accepts() {
+        let (system, _) = example();
+        let values = [1, 500, 1000, 1500, 2000, 2500, 3000, 3500, 4000];
+        let range = |v: u64| (v, v + 1);
+
+        for x in values {
+            for m in values {
+                for a in values {
+                    for s in values {
+                        let part = Part {
+                            ratings: [x, m, a, s],
+                        };
+                        let single = PartRange {
+                            ranges: [range(x), range(m), range(a), range(s)],
+                        };
+                        assert_eq!(
+                            system.count_accepted("in", single).unwrap().value(),
+                            system.accepts(&part) as u64
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
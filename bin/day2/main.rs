@@ -1,72 +1,108 @@
+use aoc23::answer::{Answer, AnswerError};
 use std::{
-    cmp,
+    cmp, fmt,
     fs::File,
     io::{self, BufRead, BufReader},
     path::Path,
 };
 
-#[derive(Debug)]
-struct Set {
+fn invalid(line: &str, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} in {:?}", reason, line),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Set {
     red: Option<u32>,
     green: Option<u32>,
     blue: Option<u32>,
 }
 
 impl Set {
-    fn new(line: &str) -> Set {
+    /// Unknown colours are ignored and a repeated colour keeps its last
+    /// count, but a cube without a count and colour or with a count that is
+    /// not a `u32` is an error.
+    pub(crate) fn new(line: &str) -> io::Result<Set> {
         let mut set = Set {
             red: None,
             green: None,
             blue: None,
         };
 
-        line.split(",")
-            .map(|c| c.trim())
-            .collect::<Vec<&str>>()
-            .iter()
-            .for_each(|c| {
-                let cube = c.split(" ").collect::<Vec<&str>>();
-                let val = cube[0].parse::<u32>().unwrap_or_default();
-                match cube[1] {
-                    "red" => set.red = Some(val),
-                    "green" => set.green = Some(val),
-                    "blue" => set.blue = Some(val),
-                    _ => (),
-                }
-            });
+        for c in line.split(",") {
+            let cube = c.split_whitespace().collect::<Vec<&str>>();
+            let [count, colour] = cube[..] else {
+                return Err(invalid(line, "expected count and colour"));
+            };
+            let val = count
+                .parse::<u32>()
+                .map_err(|_| invalid(line, "invalid cube count"))?;
+            let cubes = match colour {
+                "red" => &mut set.red,
+                "green" => &mut set.green,
+                "blue" => &mut set.blue,
+                _ => continue,
+            };
+            *cubes = Some(val);
+        }
+
+        Ok(set)
+    }
+}
 
-        set
+impl fmt::Display for Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes = [
+            (self.red, "red"),
+            (self.green, "green"),
+            (self.blue, "blue"),
+        ]
+        .iter()
+        .filter_map(|(n, colour)| n.map(|n| format!("{} {}", n, colour)))
+        .collect::<Vec<String>>();
+        write!(f, "{}", cubes.join(", "))
     }
 }
 
-#[derive(Debug)]
-struct Game {
+#[derive(Debug, Clone)]
+pub(crate) struct Game {
     id: u32,
     sets: Vec<Set>,
 }
 
 impl Game {
-    fn new(line: String) -> Game {
-        let game_and_sets: Vec<&str> = line.split(":").collect();
-        Game {
-            id: game_and_sets[0]
-                .split(" ")
-                .collect::<Vec<&str>>()
-                .get(1)
-                .unwrap()
-                .parse::<u32>()
-                .unwrap(),
-            sets: game_and_sets[1]
+    pub(crate) fn new(line: String) -> io::Result<Game> {
+        let (game, sets) = line
+            .split_once(":")
+            .ok_or_else(|| invalid(&line, "missing colon"))?;
+        Ok(Game {
+            id: game
+                .trim()
+                .strip_prefix("Game")
+                .and_then(|id| id.trim().parse::<u32>().ok())
+                .ok_or_else(|| invalid(&line, "invalid game id"))?,
+            sets: sets
                 .split(";")
-                .collect::<Vec<&str>>()
-                .iter()
                 .map(|s| Set::new(s.trim()))
-                .collect::<Vec<Set>>(),
-        }
+                .collect::<io::Result<Vec<Set>>>()?,
+        })
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sets = self
+            .sets
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "Game {}: {}", self.id, sets.join("; "))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Bag {
     red: u32,
     green: u32,
@@ -87,8 +123,8 @@ impl Bag {
         true
     }
 
-    fn power(&self) -> u32 {
-        self.red * self.green * self.blue
+    fn power(&self) -> Result<Answer, AnswerError> {
+        Answer::product([self.red, self.green, self.blue], "bag power")
     }
 
     fn find_smallest(game: &Game) -> Bag {
@@ -119,8 +155,8 @@ fn main() -> io::Result<()> {
 
     let games = lines
         .into_iter()
-        .map(|l| Game::new(l.unwrap()))
-        .collect::<Vec<Game>>();
+        .map(|l| Game::new(l?))
+        .collect::<io::Result<Vec<Game>>>()?;
 
     println!("games {}", games.len());
 
@@ -130,21 +166,22 @@ fn main() -> io::Result<()> {
         blue: 14,
     };
 
-    let ids_sum = games
-        .iter()
-        .filter(|g| bag.is_game_possible(g))
-        .fold(0, |acc, g| {
-            println!("{}", g.id);
-            acc + g.id
-        });
+    let ids_sum =
+        games
+            .iter()
+            .filter(|g| bag.is_game_possible(g))
+            .try_fold(Answer::ZERO, |acc, g| {
+                println!("{}", g.id);
+                acc.checked_add(g.id, format_args!("sum of ids at game {}", g.id))
+            })?;
 
     println!("sum of ids {}", ids_sum);
 
-    let smallest_bag_power = games
+    let powers = games
         .iter()
-        .map(|g| Bag::find_smallest(g))
-        .map(|b| b.power())
-        .fold(0, |acc, b| acc + b);
+        .map(|g| Bag::find_smallest(g).power())
+        .collect::<Result<Vec<Answer>, AnswerError>>()?;
+    let smallest_bag_power = Answer::sum(powers, "smallest bag power sum")?;
 
     println!("smallest bag power sum {}", smallest_bag_power);
 
@@ -152,8 +189,15 @@ fn main() -> io::Result<()> {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use aoc23::{
+        differential::{forall, Differential, Shrink},
+        random::Rng,
+        reference,
+    };
+    use std::str;
 
     // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
     // Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
@@ -168,7 +212,8 @@ mod tests {
     fn test_load_game() {
         let game = Game::new(
             "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue".to_string(),
-        );
+        )
+        .unwrap();
         assert_eq!(game.id, 2);
         assert_eq!(game.sets.len(), 3);
         assert_eq!(game.sets[0].red, None);
@@ -182,23 +227,68 @@ mod tests {
         assert_eq!(game.sets[2].blue, Some(1));
     }
 
+    fn load_error(line: &str) -> String {
+        Game::new(line.to_string()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_load_game_lenient() {
+        let game = Game::new("Game 1: 3 red, 2 pink; 1 green, 2 green".to_string()).unwrap();
+        assert_eq!(game.sets[0].red, Some(3));
+        assert_eq!(game.sets[1].green, Some(2));
+    }
+
+    #[test]
+    fn test_load_game_errors() {
+        assert_eq!(
+            load_error("Game 1: x red"),
+            "invalid cube count in \"x red\""
+        );
+        assert_eq!(
+            load_error("Game 1: -1 red"),
+            "invalid cube count in \"-1 red\""
+        );
+        assert_eq!(
+            load_error("Game 1: 4294967296 blue"),
+            "invalid cube count in \"4294967296 blue\""
+        );
+        assert_eq!(
+            load_error("Game 1: 3 red 4"),
+            "expected count and colour in \"3 red 4\""
+        );
+        assert_eq!(
+            load_error("Game 1 3 red"),
+            "missing colon in \"Game 1 3 red\""
+        );
+        assert_eq!(
+            load_error("Game one: 3 red"),
+            "invalid game id in \"Game one: 3 red\""
+        );
+    }
+
     #[test]
     fn test_find() {
         let games = vec![
-            Game::new("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string()),
+            Game::new("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string())
+                .unwrap(),
             Game::new(
                 "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue".to_string(),
-            ),
+            )
+            .unwrap(),
             Game::new(
                 "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
                     .to_string(),
-            ),
+            )
+            .unwrap(),
             Game::new(
                 "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red"
                     .to_string(),
-            ),
-            Game::new("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string()),
-            Game::new("Game 5: 6 red, 14 blue, 3 green; 2 blue, 6 red, 12 green".to_string()),
+            )
+            .unwrap(),
+            Game::new("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string())
+                .unwrap(),
+            Game::new("Game 5: 6 red, 14 blue, 3 green; 2 blue, 6 red, 12 green".to_string())
+                .unwrap(),
         ];
 
         let bag = Bag {
@@ -218,20 +308,26 @@ mod tests {
     #[test]
     fn test_fewest_cubes_needed() {
         let games = vec![
-            Game::new("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string()),
+            Game::new("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string())
+                .unwrap(),
             Game::new(
                 "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue".to_string(),
-            ),
+            )
+            .unwrap(),
             Game::new(
                 "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
                     .to_string(),
-            ),
+            )
+            .unwrap(),
             Game::new(
                 "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red"
                     .to_string(),
-            ),
-            Game::new("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string()),
-            Game::new("Game 5: 6 red, 14 blue, 3 green; 2 blue, 6 red, 12 green".to_string()),
+            )
+            .unwrap(),
+            Game::new("Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string())
+                .unwrap(),
+            Game::new("Game 5: 6 red, 14 blue, 3 green; 2 blue, 6 red, 12 green".to_string())
+                .unwrap(),
         ];
 
         assert_eq!(
@@ -275,10 +371,293 @@ mod tests {
             }
         );
 
-        assert_eq!(Bag::find_smallest(&games[0]).power(), 48);
-        assert_eq!(Bag::find_smallest(&games[1]).power(), 12);
-        assert_eq!(Bag::find_smallest(&games[2]).power(), 1560);
-        assert_eq!(Bag::find_smallest(&games[3]).power(), 630);
-        assert_eq!(Bag::find_smallest(&games[4]).power(), 36);
+        assert_eq!(Bag::find_smallest(&games[0]).power().unwrap().value(), 48);
+        assert_eq!(Bag::find_smallest(&games[1]).power().unwrap().value(), 12);
+        assert_eq!(Bag::find_smallest(&games[2]).power().unwrap().value(), 1560);
+        assert_eq!(Bag::find_smallest(&games[3]).power().unwrap().value(), 630);
+        assert_eq!(Bag::find_smallest(&games[4]).power().unwrap().value(), 36);
+    }
+
+    #[test]
+    fn test_power_overflow() {
+        let bag = Bag {
+            red: u32::MAX,
+            green: u32::MAX,
+            blue: 1,
+        };
+        assert_eq!(bag.power().unwrap().value(), u64::from(u32::MAX).pow(2));
+
+        let bag = Bag {
+            red: u32::MAX,
+            green: u32::MAX,
+            blue: u32::MAX,
+        };
+        assert_eq!(
+            bag.power(),
+            Err(AnswerError::Overflow("bag power".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let line = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+        let game = Game::new(line.to_string()).unwrap();
+        assert_eq!(
+            game.to_string(),
+            "Game 3: 20 red, 8 green, 6 blue; 4 red, 13 green, 5 blue; 1 red, 5 green"
+        );
+        assert_eq!(
+            Bag::find_smallest(&Game::new(game.to_string()).unwrap()),
+            Bag::find_smallest(&game)
+        );
+    }
+
+    /// Checks a fuzzer input for the game parser: no line may panic, and a
+    /// valid game must give the same answers when reformatted, both
+    /// canonically and with extra spaces around every separator.
+    fn check(data: &[u8]) {
+        let Ok(line) = str::from_utf8(data) else {
+            return;
+        };
+        let Ok(game) = Game::new(line.to_string()) else {
+            return;
+        };
+
+        let bag = Bag {
+            red: 12,
+            green: 13,
+            blue: 14,
+        };
+        let smallest = Bag::find_smallest(&game);
+        let mut reformatted = Vec::new();
+        // A set of only ignored colours prints as nothing, which is no set.
+        if game.sets.iter().all(|s| !s.to_string().is_empty()) {
+            reformatted.push(game.to_string());
+        }
+        // An ignored colour may itself contain a colon, which spacing splits.
+        if line.matches(':').count() == 1 {
+            reformatted.push(
+                line.replace(':', " : ")
+                    .replace(',', " , ")
+                    .replace(';', " ; "),
+            );
+        }
+        for reformatted in reformatted {
+            let other = Game::new(reformatted.clone()).expect(&reformatted);
+            assert_eq!(other.id, game.id);
+            assert_eq!(bag.is_game_possible(&other), bag.is_game_possible(&game));
+            assert_eq!(Bag::find_smallest(&other), smallest);
+        }
+
+        let fewest = reference::fewest_cubes(line);
+        assert_eq!(
+            fewest,
+            [smallest.red, smallest.green, smallest.blue].map(u64::from)
+        );
+        if let Ok(power) = smallest.power() {
+            assert_eq!(power.value(), fewest.iter().product::<u64>());
+        }
+    }
+
+    // Lines that used to panic, one that silently dropped a cube because of
+    // the double space, one repeating a colour within a set, where the
+    // reference has to keep the last count like day2 does, and two with
+    // ignored colours that cannot be reformatted.
+    const REPRODUCERS: [&str; 12] = [
+        "n",
+        "Game 1",
+        "Game x: 1 red",
+        "Game 1: ",
+        "Game 1: 3",
+        "Game 1: 3 red;",
+        "Game 1: 3 red,",
+        "Game  1: 3 red",
+        "Game 1:3  red",
+        "Game4:1 red,6 blue;3 green,6 red,2 green,6 blue",
+        "Game 1: 3 blue, 6 :blue",
+        "Game 1: 0 L",
+    ];
+
+    #[test]
+    fn test_fuzz_reproducers() {
+        for input in REPRODUCERS {
+            check(input.as_bytes());
+        }
+        assert!(Game::new("Game 1: 3".to_string()).is_err());
+        assert!(Game::new("Game 1: 3 red;".to_string()).is_err());
+        assert!(Game::new("Game 1: 99999999999 red".to_string()).is_err());
+        assert_eq!(Game::new("Game  1: 3 red".to_string()).unwrap().id, 1);
+        assert_eq!(
+            Game::new("Game 1:3  red".to_string()).unwrap().sets[0].red,
+            Some(3)
+        );
+    }
+
+    impl Shrink for Set {
+        /// Drops a colour while more than one is shown, or lowers a count.
+        fn shrink(&self) -> Vec<Set> {
+            let counts = [self.red, self.green, self.blue];
+            let shown = counts.iter().flatten().count();
+            let mut smaller = Vec::new();
+            for c in 0..3 {
+                let Some(n) = counts[c] else {
+                    continue;
+                };
+                let mut options = n.shrink().into_iter().map(Some).collect::<Vec<_>>();
+                if shown > 1 {
+                    options.insert(0, None);
+                }
+                for option in options {
+                    let mut counts = counts;
+                    counts[c] = option;
+                    smaller.push(Set {
+                        red: counts[0],
+                        green: counts[1],
+                        blue: counts[2],
+                    });
+                }
+            }
+            smaller
+        }
+    }
+
+    impl Shrink for Game {
+        /// Drops a set while more than one is left, then shrinks the id and
+        /// each set.
+        fn shrink(&self) -> Vec<Game> {
+            let mut smaller = Vec::new();
+            if self.sets.len() > 1 {
+                for i in 0..self.sets.len() {
+                    let mut sets = self.sets.clone();
+                    sets.remove(i);
+                    smaller.push(Game { id: self.id, sets });
+                }
+            }
+            for id in self.id.shrink() {
+                smaller.push(Game {
+                    id,
+                    sets: self.sets.clone(),
+                });
+            }
+            for (i, set) in self.sets.iter().enumerate() {
+                for s in set.shrink() {
+                    let mut sets = self.sets.clone();
+                    sets[i] = s;
+                    smaller.push(Game { id: self.id, sets });
+                }
+            }
+            smaller
+        }
+    }
+
+    fn random_game(rng: &mut Rng) -> Game {
+        let sets = (0..rng.range(1..6))
+            .map(|_| {
+                let mut counts = [None; 3];
+                while counts.iter().all(Option::is_none) {
+                    for count in &mut counts {
+                        if rng.chance(60) {
+                            *count = Some(rng.range(0..21) as u32);
+                        }
+                    }
+                }
+                Set {
+                    red: counts[0],
+                    green: counts[1],
+                    blue: counts[2],
+                }
+            })
+            .collect();
+        Game {
+            id: rng.range(1..101) as u32,
+            sets,
+        }
+    }
+
+    fn colours(bag: &Bag) -> [u32; 3] {
+        [bag.red, bag.green, bag.blue]
+    }
+
+    fn bag(colours: [u32; 3]) -> Bag {
+        Bag {
+            red: colours[0],
+            green: colours[1],
+            blue: colours[2],
+        }
+    }
+
+    #[test]
+    fn test_games_round_trip() {
+        let result = forall(50, 500, random_game, |game| {
+            Game::new(game.to_string()).is_ok_and(|g| g.to_string() == game.to_string())
+        });
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_smallest_bag_agrees_with_reference() {
+        Differential::new()
+            .register("reference", |game: &Game| {
+                reference::fewest_cubes(&game.to_string())
+            })
+            .register("find_smallest", |game: &Game| {
+                colours(&Bag::find_smallest(game)).map(u64::from)
+            })
+            .assert_agree(51, 1000, random_game);
+    }
+
+    #[test]
+    fn test_smallest_bag_makes_game_possible() {
+        let result = forall(50, 1000, random_game, |game| {
+            Bag::find_smallest(game).is_game_possible(game)
+        });
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_smaller_bag_makes_game_impossible() {
+        let result = forall(50, 1000, random_game, |game| {
+            let smallest = colours(&Bag::find_smallest(game));
+            (0..3).filter(|&c| smallest[c] > 0).all(|c| {
+                [0, smallest[c] / 2, smallest[c] - 1].iter().all(|&less| {
+                    // Every other colour has as many cubes as could be wanted.
+                    let mut counts = [u32::MAX; 3];
+                    counts[c] = less;
+                    !bag(counts).is_game_possible(game)
+                })
+            })
+        });
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_power_is_monotonic() {
+        let result = forall(50, 1000, random_game, |game| {
+            let smallest = colours(&Bag::find_smallest(game));
+            let power = bag(smallest).power().unwrap();
+            (0..3).all(|c| {
+                [1, 2, 100].iter().all(|&more| {
+                    let mut counts = smallest;
+                    counts[c] += more;
+                    bag(counts).power().unwrap() >= power
+                })
+            })
+        });
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_failures_shrink_to_minimal_game() {
+        // Only looking at the first set is wrong as soon as a later set needs
+        // more cubes, which takes two sets with a single cube between them.
+        let first_set_only = |game: &Game| {
+            let first = Game {
+                id: game.id,
+                sets: game.sets[..1].to_vec(),
+            };
+            Bag::find_smallest(&first).is_game_possible(game)
+        };
+        let minimal = forall(50, 1000, random_game, first_set_only).unwrap_err();
+        assert_eq!(minimal.to_string(), "Game 0: 0 blue; 1 blue");
     }
 }
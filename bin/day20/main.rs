@@ -0,0 +1,444 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    env, io,
+};
+
+use aoc23::{
+    answer::{Answer, AnswerError},
+    graph::Graph,
+    input, math,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    Output,
+}
+
+#[derive(Debug, PartialEq)]
+struct Module {
+    name: String,
+    kind: Kind,
+}
+
+impl Module {
+    fn label(&self) -> String {
+        match self.kind {
+            Kind::FlipFlop => format!("%{}", self.name),
+            Kind::Conjunction => format!("&{}", self.name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+struct Network {
+    graph: Graph<Module, ()>,
+    broadcaster: usize,
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl Network {
+    /// Fails on a line that is not `module -> outputs`, a module that is not
+    /// a flip-flop, a conjunction or the broadcaster, or a missing
+    /// broadcaster.
+    fn new(lines: &[String]) -> io::Result<Network> {
+        let mut graph = Graph::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut wiring = Vec::new();
+
+        for line in lines {
+            let (module, outputs) = line
+                .split_once(" -> ")
+                .ok_or_else(|| invalid(format!("expected \" -> \" in {:?}", line)))?;
+            let (kind, name) = match module.chars().next() {
+                Some('%') => (Kind::FlipFlop, &module[1..]),
+                Some('&') => (Kind::Conjunction, &module[1..]),
+                _ if module == "broadcaster" => (Kind::Broadcaster, module),
+                _ => return Err(invalid(format!("unknown module {:?}", module))),
+            };
+            if name.is_empty() {
+                return Err(invalid(format!("missing module name in {:?}", line)));
+            }
+
+            let i = graph.add_node(Module {
+                name: name.to_string(),
+                kind,
+            });
+            index.insert(name.to_string(), i);
+            wiring.push((i, outputs.split(", ").collect::<Vec<&str>>()));
+        }
+
+        for (from, outputs) in wiring {
+            for output in outputs {
+                let to = *index.entry(output.to_string()).or_insert_with(|| {
+                    graph.add_node(Module {
+                        name: output.to_string(),
+                        kind: Kind::Output,
+                    })
+                });
+                graph.add_edge(from, to, ());
+            }
+        }
+
+        let broadcaster = graph
+            .find(|m| m.kind == Kind::Broadcaster)
+            .ok_or_else(|| invalid("no broadcaster".to_string()))?;
+
+        Ok(Network { graph, broadcaster })
+    }
+
+    fn to_dot(&self) -> String {
+        self.graph.to_dot("network", true, |m| m.label())
+    }
+}
+
+/// Where a pulse comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    Button,
+    Module(usize),
+}
+
+/// Flip-flop and conjunction state of a network, with counts of the pulses
+/// sent so far.
+struct Simulator<'a> {
+    network: &'a Network,
+    inputs: Vec<Vec<usize>>,
+    on: Vec<bool>,
+    memory: Vec<Vec<bool>>,
+    low: u64,
+    high: u64,
+    presses: u64,
+}
+
+impl<'a> Simulator<'a> {
+    fn new(network: &'a Network) -> Simulator<'a> {
+        let inputs = (0..network.graph.len())
+            .map(|i| network.graph.predecessors(i))
+            .collect::<Vec<Vec<usize>>>();
+        let memory = inputs
+            .iter()
+            .map(|i| vec![false; i.len()])
+            .collect::<Vec<Vec<bool>>>();
+
+        Simulator {
+            network,
+            on: vec![false; network.graph.len()],
+            inputs,
+            memory,
+            low: 0,
+            high: 0,
+            presses: 0,
+        }
+    }
+
+    /// Presses the button once, calling `watch` with every pulse as
+    /// `(from, to, high)`.
+    fn push_button<F>(&mut self, mut watch: F)
+    where
+        F: FnMut(usize, usize, bool),
+    {
+        self.presses += 1;
+
+        let mut queue = VecDeque::from([(Source::Button, self.network.broadcaster, false)]);
+        while let Some((from, to, high)) = queue.pop_front() {
+            if high {
+                self.high += 1;
+            } else {
+                self.low += 1;
+            }
+            if let Source::Module(from) = from {
+                watch(from, to, high);
+            }
+
+            let send = match self.network.graph.node(to).kind {
+                Kind::Broadcaster => Some(high),
+                Kind::FlipFlop if !high => {
+                    self.on[to] = !self.on[to];
+                    Some(self.on[to])
+                }
+                Kind::FlipFlop => None,
+                Kind::Conjunction => {
+                    let slot = self.inputs[to]
+                        .iter()
+                        .position(|i| Source::Module(*i) == from)
+                        .unwrap();
+                    self.memory[to][slot] = high;
+                    Some(!self.memory[to].iter().all(|m| *m))
+                }
+                Kind::Output => None,
+            };
+
+            if let Some(pulse) = send {
+                for (next, _) in self.network.graph.edges(to) {
+                    queue.push_back((Source::Module(to), *next, pulse));
+                }
+            }
+        }
+    }
+
+    fn pulse_product(&mut self, presses: u64) -> Result<Answer, AnswerError> {
+        for _ in 0..presses {
+            self.push_button(|_, _, _| ());
+        }
+
+        Answer::new(self.low).checked_mul(self.high, "pulse product")
+    }
+
+    /// Presses needed until `target` gets a low pulse, assuming it is fed by a
+    /// single conjunction whose inputs each send a high pulse periodically.
+    /// Gives up with `None` if some input has not gone high within `limit`
+    /// presses, and fails if the cycles only line up beyond a `u64`.
+    fn presses_until_low(
+        &mut self,
+        target: &str,
+        limit: u64,
+    ) -> Result<Option<Answer>, AnswerError> {
+        let graph = &self.network.graph;
+        let Some(target) = graph.find(|m| m.name == target) else {
+            return Ok(None);
+        };
+        let [hub] = graph.predecessors(target)[..] else {
+            return Ok(None);
+        };
+        if graph.node(hub).kind != Kind::Conjunction {
+            return Ok(None);
+        }
+        let feeders = graph.predecessors(hub);
+
+        let mut first_high: Vec<Option<u64>> = vec![None; feeders.len()];
+        while first_high.iter().any(|p| p.is_none()) {
+            if self.presses >= limit {
+                return Ok(None);
+            }
+
+            let presses = self.presses + 1;
+            self.push_button(|from, to, high| {
+                if to == hub && high {
+                    if let Some(i) = feeders.iter().position(|f| *f == from) {
+                        first_high[i].get_or_insert(presses);
+                    }
+                }
+            });
+        }
+
+        first_high
+            .into_iter()
+            .flatten()
+            .try_fold(1, math::lcm)
+            .map(|presses| Some(Answer::new(presses)))
+            .ok_or_else(|| AnswerError::Overflow("presses until low".to_string()))
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day20");
+
+    let lines = input::lines("./bin/day20/input")?.collect::<io::Result<Vec<String>>>()?;
+    let network = Network::new(&lines)?;
+
+    if env::args().any(|a| a == "--dot") {
+        print!("{}", network.to_dot());
+        return Ok(());
+    }
+
+    println!("modules {}", network.graph.len());
+    println!(
+        "pulse product {}",
+        Simulator::new(&network).pulse_product(1000)?
+    );
+    println!(
+        "presses until rx {}",
+        Simulator::new(&network)
+            .presses_until_low("rx", 1 << 20)?
+            .ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidData,
+                "rx never gets a low pulse"
+            ))?
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // broadcaster -> a, b, c
+    // %a -> b
+    // %b -> c
+    // %c -> inv
+    // &inv -> a
+    //
+    // 8000 low and 4000 high pulses after 1000 presses, product 32000000
+
+    fn network(lines: &[&str]) -> Network {
+        Network::new(&lines.iter().map(|l| l.to_string()).collect::<Vec<String>>()).unwrap()
+    }
+
+    fn example() -> Network {
+        network(&[
+            "broadcaster -> a, b, c",
+            "%a -> b",
+            "%b -> c",
+            "%c -> inv",
+            "&inv -> a",
+        ])
+    }
+
+    fn example_output() -> Network {
+        network(&[
+            "broadcaster -> a",
+            "%a -> inv, con",
+            "&inv -> b",
+            "%b -> con",
+            "&con -> output",
+        ])
+    }
+
+    // Two flip-flop counters feeding rx through inverters and a hub, sending
+    // high pulses to the hub every 2 and every 4 presses
+    fn counters() -> Network {
+        network(&[
+            "broadcaster -> a, x",
+            "%a -> ia",
+            "&ia -> hub",
+            "%x -> y",
+            "%y -> iy",
+            "&iy -> hub",
+            "&hub -> rx",
+        ])
+    }
+
+    #[test]
+    fn test_load() {
+        let network = example_output();
+        assert_eq!(network.graph.len(), 6);
+        assert_eq!(
+            network.graph.node(5),
+            &Module {
+                name: "output".to_string(),
+                kind: Kind::Output
+            }
+        );
+        assert_eq!(network.graph.predecessors(4), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let error = |lines: &[&str]| {
+            let lines = lines.iter().map(|l| l.to_string()).collect::<Vec<String>>();
+            Network::new(&lines).err().map(|e| e.to_string())
+        };
+        assert_eq!(
+            error(&["broadcaster -> a", "a -> b"]).as_deref(),
+            Some("unknown module \"a\"")
+        );
+        assert_eq!(
+            error(&["%a -> b", "&b -> a"]).as_deref(),
+            Some("no broadcaster")
+        );
+        assert_eq!(
+            error(&["broadcaster a"]).as_deref(),
+            Some("expected \" -> \" in \"broadcaster a\"")
+        );
+        assert_eq!(
+            error(&["broadcaster -> a", "% -> a"]).as_deref(),
+            Some("missing module name in \"% -> a\"")
+        );
+        assert_eq!(error(&["broadcaster -> a", "%a -> out"]), None);
+    }
+
+    #[test]
+    fn test_push_button() {
+        let network = example();
+        let mut simulator = Simulator::new(&network);
+        let mut pulses = Vec::new();
+        simulator.push_button(|from, to, high| pulses.push((from, to, high)));
+
+        assert_eq!(pulses.len(), 11);
+        assert_eq!((simulator.low, simulator.high), (8, 4));
+        assert_eq!(simulator.on[1..4], [false, false, false]);
+    }
+
+    #[test]
+    fn test_pulse_product() {
+        assert_eq!(
+            Simulator::new(&example()).pulse_product(1000),
+            Ok(Answer::new(32000000))
+        );
+        assert_eq!(
+            Simulator::new(&example_output()).pulse_product(1000),
+            Ok(Answer::new(11687500))
+        );
+    }
+
+    #[test]
+    fn test_presses_until_low() {
+        let network = counters();
+        assert_eq!(
+            Simulator::new(&network).presses_until_low("rx", 100),
+            Ok(Some(Answer::new(4)))
+        );
+
+        let rx = network.graph.find(|m| m.name == "rx").unwrap();
+        let mut simulator = Simulator::new(&network);
+        let mut low = false;
+        while !low {
+            simulator.push_button(|_, to, high| low |= to == rx && !high);
+        }
+        assert_eq!(simulator.presses, 4);
+    }
+
+    #[test]
+    fn test_presses_until_low_missing() {
+        assert_eq!(
+            Simulator::new(&example()).presses_until_low("rx", 100),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_presses_until_low_feeder_never_high() {
+        // Nothing ever sends a pulse to `never`, so it never goes high.
+        let network = network(&[
+            "broadcaster -> a",
+            "%a -> ia",
+            "&ia -> hub",
+            "&never -> hub",
+            "&hub -> rx",
+        ]);
+        let mut simulator = Simulator::new(&network);
+        assert_eq!(simulator.presses_until_low("rx", 1000), Ok(None));
+        assert_eq!(simulator.presses, 1000);
+    }
+
+    #[test]
+    fn test_presses_until_low_hub_not_conjunction() {
+        let flip_flop = network(&["broadcaster -> a", "%a -> hub", "%hub -> rx"]);
+        assert_eq!(
+            Simulator::new(&flip_flop).presses_until_low("rx", 100),
+            Ok(None)
+        );
+
+        let two_hubs = network(&["broadcaster -> a, b", "&a -> rx", "&b -> rx"]);
+        assert_eq!(
+            Simulator::new(&two_hubs).presses_until_low("rx", 100),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = example().to_dot();
+        assert!(dot.starts_with("digraph network {\n"));
+        assert!(dot.contains("n4 [label=\"&inv\"];"));
+        assert!(dot.contains("n1 [label=\"%a\"];"));
+        assert!(dot.contains("n4 -> n1;"));
+    }
+}
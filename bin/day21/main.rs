@@ -0,0 +1,169 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io,
+};
+
+use aoc23::{
+    grid::{Direction, Grid},
+    input,
+    math::DifferenceTable,
+};
+
+struct Garden {
+    grid: Grid<char>,
+    start: (i64, i64),
+}
+
+impl Garden {
+    fn new(grid: Grid<char>) -> Garden {
+        let (x, y) = grid.find(|c| *c == 'S').unwrap();
+        Garden {
+            grid,
+            start: (x as i64, y as i64),
+        }
+    }
+
+    fn is_plot(&self, x: i64, y: i64, infinite: bool) -> bool {
+        if infinite {
+            *self.grid.get_wrapped(x, y) != '#'
+        } else {
+            self.grid.contains(x, y) && *self.grid.get(x as usize, y as usize) != '#'
+        }
+    }
+
+    /// Number of plots reachable in exactly `steps` steps. A plot reached in
+    /// fewer steps of the same parity can be revisited by stepping back and
+    /// forth, so this counts plots at an even distance short of `steps`.
+    fn reachable(&self, steps: u64, infinite: bool) -> u64 {
+        let mut seen = HashSet::from([self.start]);
+        let mut queue = VecDeque::from([(self.start, 0)]);
+        let mut count = 0;
+
+        while let Some(((x, y), dist)) = queue.pop_front() {
+            if dist % 2 == steps % 2 {
+                count += 1;
+            }
+            if dist == steps {
+                continue;
+            }
+
+            for dir in Direction::ALL {
+                let (dx, dy) = dir.delta();
+                let next = (x + dx, y + dy);
+                if self.is_plot(next.0, next.1, infinite) && seen.insert(next) {
+                    queue.push_back((next, dist + 1));
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Plots reachable in `steps` on the infinite garden, extrapolated from
+    /// three samples one garden width apart. Relies on the reachable area
+    /// growing quadratically in whole garden widths, as it does when the
+    /// start row and column are clear.
+    fn reachable_extrapolated(&self, steps: u64) -> Option<i64> {
+        let size = self.grid.width() as u64;
+        let (cycles, rest) = (steps / size, steps % size);
+        if cycles < 3 {
+            return i64::try_from(self.reachable(steps, true)).ok();
+        }
+
+        let samples = (0..3)
+            .map(|i| i64::try_from(self.reachable(rest + i * size, true)).ok())
+            .collect::<Option<Vec<i64>>>()?;
+
+        DifferenceTable::new(&samples)?.extrapolate_forward_by(cycles - 2)
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day21");
+
+    let lines = input::lines("./bin/day21/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = Grid::from_lines(&lines);
+    grid.ensure_rectangular()?;
+
+    let garden = Garden::new(grid);
+    println!("reachable in 64 {}", garden.reachable(64, false));
+    println!(
+        "reachable in 26501365 {}",
+        garden.reachable_extrapolated(26501365).unwrap()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ...........
+    // .....###.#.
+    // .###.##..#.
+    // ..#.#...#..
+    // ....#.#....
+    // .##..S####.
+    // .##..#...#.
+    // .......##..
+    // .##.#.####.
+    // .##..##.##.
+    // ...........
+    //
+    // 16 plots reachable in 6 steps, 6536 in 100 steps on the infinite garden
+
+    fn example() -> Garden {
+        Garden::new(Grid::from_lines(&[
+            "...........",
+            ".....###.#.",
+            ".###.##..#.",
+            "..#.#...#..",
+            "....#.#....",
+            ".##..S####.",
+            ".##..#...#.",
+            ".......##..",
+            ".##.#.####.",
+            ".##..##.##.",
+            "...........",
+        ]))
+    }
+
+    fn open_garden() -> Garden {
+        Garden::new(Grid::from_lines(&[
+            ".......", ".#...#.", "..#....", "...S...", "....#..", ".#...#.", ".......",
+        ]))
+    }
+
+    #[test]
+    fn test_reachable() {
+        let garden = example();
+        assert_eq!(garden.reachable(1, false), 2);
+        assert_eq!(garden.reachable(2, false), 4);
+        assert_eq!(garden.reachable(3, false), 6);
+        assert_eq!(garden.reachable(6, false), 16);
+    }
+
+    #[test]
+    fn test_reachable_infinite() {
+        let garden = example();
+        assert_eq!(garden.reachable(6, true), 16);
+        assert_eq!(garden.reachable(10, true), 50);
+        assert_eq!(garden.reachable(50, true), 1594);
+        assert_eq!(garden.reachable(100, true), 6536);
+    }
+
+    #[test]
+    fn test_reachable_extrapolated() {
+        let garden = open_garden();
+        for steps in [3, 10, 17, 24, 31, 45, 52, 100] {
+            assert_eq!(
+                garden.reachable_extrapolated(steps),
+                Some(garden.reachable(steps, true) as i64),
+                "{} steps",
+                steps
+            );
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, io};
+
+use aoc23::{
+    answer::Answer,
+    geometry::{Box3, Point3},
+    input,
+};
+
+fn parse_brick(line: &str) -> Box3 {
+    let (a, b) = line.split_once('~').unwrap();
+    let point = |s: &str| {
+        let c = s
+            .split(',')
+            .map(|n| n.parse::<i64>().unwrap())
+            .collect::<Vec<i64>>();
+        Point3::new(c[0], c[1], c[2])
+    };
+
+    Box3::new(point(a), point(b))
+}
+
+/// Settled bricks, lowest first, with which bricks each rests on.
+struct Stack {
+    bricks: Vec<Box3>,
+    supports: Vec<Vec<usize>>,
+    supported_by: Vec<Vec<usize>>,
+}
+
+impl Stack {
+    fn settle(mut bricks: Vec<Box3>) -> Stack {
+        bricks.sort_by_key(|b| b.min.z);
+
+        // highest settled z and brick at each (x, y)
+        let mut tops: HashMap<(i64, i64), (i64, usize)> = HashMap::new();
+        let mut supports = vec![Vec::new(); bricks.len()];
+        let mut supported_by = vec![Vec::new(); bricks.len()];
+
+        for i in 0..bricks.len() {
+            let floor = bricks[i]
+                .footprint()
+                .filter_map(|p| tops.get(&p))
+                .map(|(z, _)| *z)
+                .max()
+                .unwrap_or(0);
+
+            let mut below = bricks[i]
+                .footprint()
+                .filter_map(|p| tops.get(&p))
+                .filter(|(z, _)| *z == floor)
+                .map(|(_, j)| *j)
+                .collect::<Vec<usize>>();
+            below.sort_unstable();
+            below.dedup();
+
+            for j in &below {
+                supports[*j].push(i);
+            }
+            supported_by[i] = below;
+
+            bricks[i] = bricks[i].translate(Point3::new(0, 0, floor + 1 - bricks[i].min.z));
+            for p in bricks[i].footprint() {
+                tops.insert(p, (bricks[i].max.z, i));
+            }
+        }
+
+        Stack {
+            bricks,
+            supports,
+            supported_by,
+        }
+    }
+
+    fn can_disintegrate(&self, i: usize) -> bool {
+        self.supports[i]
+            .iter()
+            .all(|j| self.supported_by[*j].len() > 1)
+    }
+
+    /// Number of other bricks that fall when brick `i` is removed.
+    fn chain_reaction(&self, i: usize) -> usize {
+        let mut fallen = vec![false; self.bricks.len()];
+        fallen[i] = true;
+
+        // bricks are settled lowest first, so every supporter of a brick
+        // comes before it
+        let mut count = 0;
+        for j in i + 1..self.bricks.len() {
+            if !self.supported_by[j].is_empty() && self.supported_by[j].iter().all(|k| fallen[*k]) {
+                fallen[j] = true;
+                count += 1;
+            }
+        }
+
+        count
+    }
+}
+
+fn main() -> io::Result<()> {
+    println!("day22");
+
+    let bricks = input::lines("./bin/day22/input")?
+        .map(|l| l.map(|l| parse_brick(&l)))
+        .collect::<io::Result<Vec<Box3>>>()?;
+
+    let stack = Stack::settle(bricks);
+    println!("bricks {}", stack.bricks.len());
+
+    let safe = (0..stack.bricks.len())
+        .filter(|i| stack.can_disintegrate(*i))
+        .count();
+    println!("safe to disintegrate {}", safe);
+
+    let falling = Answer::sum(
+        (0..stack.bricks.len()).map(|i| stack.chain_reaction(i)),
+        "falling bricks",
+    )?;
+    println!("falling bricks {}", falling);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1,0,1~1,2,1   A
+    // 0,0,2~2,0,2   B
+    // 0,2,3~2,2,3   C
+    // 0,0,4~0,2,4   D
+    // 2,0,5~2,2,5   E
+    // 0,1,6~2,1,6   F
+    // 1,1,8~1,1,9   G
+    //
+    // 5 bricks can be disintegrated, 7 fall in total across chain reactions
+
+    fn example() -> Stack {
+        Stack::settle(
+            [
+                "1,0,1~1,2,1",
+                "0,0,2~2,0,2",
+                "0,2,3~2,2,3",
+                "0,0,4~0,2,4",
+                "2,0,5~2,2,5",
+                "0,1,6~2,1,6",
+                "1,1,8~1,1,9",
+            ]
+            .iter()
+            .map(|l| parse_brick(l))
+            .collect::<Vec<Box3>>(),
+        )
+    }
+
+    #[test]
+    fn test_parse_brick() {
+        assert_eq!(
+            parse_brick("2,2,2~0,2,2"),
+            Box3::new(Point3::new(0, 2, 2), Point3::new(2, 2, 2))
+        );
+    }
+
+    #[test]
+    fn test_settle() {
+        let stack = example();
+        let z = stack.bricks.iter().map(|b| b.min.z).collect::<Vec<i64>>();
+        assert_eq!(z, vec![1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(stack.supports[0], vec![1, 2]);
+        assert_eq!(stack.supported_by[3], vec![1, 2]);
+        assert_eq!(stack.supported_by[6], vec![5]);
+
+        for (i, a) in stack.bricks.iter().enumerate() {
+            for b in &stack.bricks[i + 1..] {
+                assert!(!a.intersects(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_can_disintegrate() {
+        let stack = example();
+        assert_eq!(
+            (0..7)
+                .map(|i| stack.can_disintegrate(i))
+                .collect::<Vec<bool>>(),
+            vec![false, true, true, true, true, false, true]
+        );
+    }
+
+    #[test]
+    fn test_chain_reaction() {
+        let stack = example();
+        assert_eq!(
+            (0..7)
+                .map(|i| stack.chain_reaction(i))
+                .collect::<Vec<usize>>(),
+            vec![6, 0, 0, 0, 0, 1, 0]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::io;
+
+use aoc23::{
+    graph::{self, Graph},
+    grid::{Direction, Grid},
+    input,
+};
+
+struct Maze {
+    grid: Grid<char>,
+    start: (usize, usize),
+    goal: (usize, usize),
+}
+
+impl Maze {
+    /// Fails unless the first and last rows each have a path tile.
+    fn new(grid: Grid<char>) -> io::Result<Maze> {
+        let path = |y: Option<usize>, name: &str| {
+            y.and_then(|y| Some((grid.row(y).iter().position(|c| *c == '.')?, y)))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("no path in the {} row", name),
+                    )
+                })
+        };
+        let start = path((grid.height() > 0).then_some(0), "first")?;
+        let goal = path(grid.height().checked_sub(1), "last")?;
+
+        Ok(Maze { grid, start, goal })
+    }
+
+    fn slope(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    fn moves(&self, (x, y): (usize, usize), slippery: bool) -> Vec<(usize, usize)> {
+        let dirs = match Maze::slope(*self.grid.get(x, y)) {
+            Some(dir) if slippery => vec![dir],
+            _ => Direction::ALL.to_vec(),
+        };
+
+        dirs.iter()
+            .filter_map(|d| self.grid.step(x, y, *d))
+            .filter(|(nx, ny)| *self.grid.get(*nx, *ny) != '#')
+            .collect::<Vec<(usize, usize)>>()
+    }
+
+    fn junctions(&self, slippery: bool) -> Graph<(usize, usize), usize> {
+        graph::compress_grid(
+            &self.grid,
+            &[self.start, self.goal],
+            |c| *c != '#',
+            |p| self.moves(p, slippery),
+        )
+    }
+
+    fn longest_walk(&self, slippery: bool) -> Option<usize> {
+        let graph = self.junctions(slippery);
+        let start = graph.find(|n| *n == self.start)?;
+        let goal = graph.find(|n| *n == self.goal)?;
+
+        let mut visited = vec![false; graph.len()];
+        visited[start] = true;
+        longest_path(&graph, start, goal, &mut visited)
+    }
+}
+
+/// Longest simple path from `from` to `goal`, with the nodes already on the
+/// path set in `visited`. `visited` is left as it was found.
+fn longest_path<N>(
+    graph: &Graph<N, usize>,
+    from: usize,
+    goal: usize,
+    visited: &mut [bool],
+) -> Option<usize> {
+    if from == goal {
+        return Some(0);
+    }
+
+    let mut longest = None;
+    for &(to, steps) in graph.edges(from) {
+        if visited[to] {
+            continue;
+        }
+
+        visited[to] = true;
+        let walk = longest_path(graph, to, goal, visited).map(|rest| rest + steps);
+        visited[to] = false;
+        longest = longest.max(walk);
+    }
+
+    longest
+}
+
+fn main() -> io::Result<()> {
+    println!("day23");
+
+    let lines = input::lines("./bin/day23/input")?.collect::<io::Result<Vec<String>>>()?;
+
+    let grid = Grid::from_lines(&lines);
+    grid.ensure_rectangular()?;
+
+    let maze = Maze::new(grid)?;
+    let walk = |slippery: bool| {
+        maze.longest_walk(slippery)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no walk from start to goal"))
+    };
+    println!("junctions {}", maze.junctions(false).len());
+    println!("longest slippery walk {}", walk(true)?);
+    println!("longest dry walk {}", walk(false)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Longest hike is 94 steps when respecting slopes, 154 when ignoring them
+
+    fn example() -> Maze {
+        Maze::new(Grid::from_lines(&[
+            "#.#####################",
+            "#.......#########...###",
+            "#######.#########.#.###",
+            "###.....#.>.>.###.#.###",
+            "###v#####.#v#.###.#.###",
+            "###.>...#.#.#.....#...#",
+            "###v###.#.#.#########.#",
+            "###...#.#.#.......#...#",
+            "#####.#.#.#######.#.###",
+            "#.....#.#.#.......#...#",
+            "#.#####.#.#.#########v#",
+            "#.#...#...#...###...>.#",
+            "#.#.#v#######v###.###v#",
+            "#...#.>.#...>.>.#.###.#",
+            "#####v#.#.###v#.#.###.#",
+            "#.....#...#...#.#.#...#",
+            "#.#########.###.#.#.###",
+            "#...###...#...#...#.###",
+            "###.###.#.###v#####v###",
+            "#...#...#.#.>.>.#.>.###",
+            "#.###.###.#.###.#.#v###",
+            "#.....###...###...#...#",
+            "#####################.#",
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_moves() {
+        let maze = example();
+        assert_eq!(maze.start, (1, 0));
+        assert_eq!(maze.goal, (21, 22));
+        assert_eq!(maze.moves((3, 4), true), vec![(3, 5)]);
+        assert_eq!(maze.moves((3, 4), false), vec![(3, 3), (3, 5)]);
+    }
+
+    #[test]
+    fn test_junctions() {
+        let maze = example();
+        let graph = maze.junctions(false);
+        assert_eq!(graph.len(), 9);
+        let first = graph.find(|n| *n == (3, 5)).unwrap();
+        assert_eq!(graph.edges(0), &[(first, 15)]);
+
+        let slippery = maze.junctions(true);
+        assert!(slippery.edge_count() < graph.edge_count());
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let error = |lines: &[&str]| {
+            Maze::new(Grid::from_lines(lines))
+                .err()
+                .map(|e| e.to_string())
+        };
+        assert_eq!(
+            error(&["###", "#.#"]).as_deref(),
+            Some("no path in the first row")
+        );
+        assert_eq!(
+            error(&["#.#", "###"]).as_deref(),
+            Some("no path in the last row")
+        );
+        assert_eq!(error(&[]).as_deref(), Some("no path in the first row"));
+        assert_eq!(error(&["#.#", "#.#"]), None);
+    }
+
+    #[test]
+    fn test_longest_walk() {
+        let maze = example();
+        assert_eq!(maze.longest_walk(true), Some(94));
+        assert_eq!(maze.longest_walk(false), Some(154));
+    }
+
+    #[test]
+    fn test_longest_walk_many_junctions() {
+        // A corridor with a dead end below every other cell has a junction
+        // at each of them, more than a u64 visited set could hold.
+        let lines = [
+            format!("#.{}", "#".repeat(141)),
+            format!("#{}#", ".".repeat(141)),
+            format!("#{}.#", ".#".repeat(70)),
+            format!("{}.#", "#".repeat(141)),
+        ];
+        let maze = Maze::new(Grid::from_lines(&lines)).unwrap();
+        assert!(maze.junctions(false).len() > 64);
+        assert_eq!(maze.longest_walk(false), Some(143));
+    }
+}
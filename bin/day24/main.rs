@@ -0,0 +1,381 @@
+use std::io;
+
+use aoc23::{
+    input,
+    math::{self, Rational},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Hailstone {
+    pos: [i128; 3],
+    vel: [i128; 3],
+}
+
+impl Hailstone {
+    fn new(line: &str) -> Hailstone {
+        let (pos, vel) = line.split_once('@').unwrap();
+        let parse = |s: &str| {
+            let v = s
+                .split(',')
+                .map(|n| n.trim().parse::<i128>().unwrap())
+                .collect::<Vec<i128>>();
+            [v[0], v[1], v[2]]
+        };
+
+        Hailstone {
+            pos: parse(pos),
+            vel: parse(vel),
+        }
+    }
+
+    /// Where the xy paths of two hailstones cross, if both reach it in the
+    /// future. An error if the crossing overflows.
+    fn intersect_xy(&self, other: &Hailstone) -> io::Result<Option<(Rational, Rational)>> {
+        self.checked_intersect_xy(other).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("crossing of {:?} and {:?} overflows", self, other),
+            )
+        })
+    }
+
+    fn checked_intersect_xy(&self, other: &Hailstone) -> Option<Option<(Rational, Rational)>> {
+        let cross = |a: [i128; 3], b: [i128; 3]| {
+            a[0].checked_mul(b[1])?.checked_sub(a[1].checked_mul(b[0])?)
+        };
+        let det = cross(other.vel, self.vel)?;
+        if det == 0 {
+            return Some(None);
+        }
+
+        let d = [
+            other.pos[0].checked_sub(self.pos[0])?,
+            other.pos[1].checked_sub(self.pos[1])?,
+            0,
+        ];
+        let t = Rational::new(cross(other.vel, d)?, det)?;
+        let s = Rational::new(cross(self.vel, d)?, det)?;
+        if t < Rational::ZERO || s < Rational::ZERO {
+            return Some(None);
+        }
+
+        let at = |axis: usize| {
+            Rational::from(self.vel[axis])
+                .checked_mul(t)?
+                .checked_add(Rational::from(self.pos[axis]))
+        };
+        Some(Some((at(0)?, at(1)?)))
+    }
+
+    /// Whether a rock thrown from `pos` at `vel` meets this hailstone at the
+    /// same time on every axis, now or later. `None` on overflow.
+    fn is_hit_by(&self, pos: [Rational; 3], vel: [Rational; 3]) -> Option<bool> {
+        let mut time = None;
+        for axis in 0..3 {
+            let gap = pos[axis].checked_sub(Rational::from(self.pos[axis]))?;
+            let closing = Rational::from(self.vel[axis]).checked_sub(vel[axis])?;
+            if closing.is_zero() {
+                if !gap.is_zero() {
+                    return Some(false);
+                }
+                continue;
+            }
+
+            let t = gap.checked_div(closing)?;
+            if t < Rational::ZERO || time.is_some_and(|time| time != t) {
+                return Some(false);
+            }
+            time = Some(t);
+        }
+
+        Some(true)
+    }
+}
+
+fn count_intersections(hail: &[Hailstone], min: i128, max: i128) -> io::Result<usize> {
+    // Against whole numbers the floor and ceiling compare like the fraction.
+    let inside = |v: Rational| min <= v.floor() && v.ceil() <= max;
+
+    let mut count = 0;
+    for (i, a) in hail.iter().enumerate() {
+        for b in &hail[i + 1..] {
+            if let Some((x, y)) = a.intersect_xy(b)? {
+                if inside(x) && inside(y) {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Position and velocity of a rock thrown along axes `a` and `b` that hits
+/// every hailstone, solved as `[pa, pb, va, vb]` from the first five.
+///
+/// Each hailstone gives `(Pa - pa)(Vb - vb) = (Pb - pb)(Va - va)`, and the
+/// non-linear `Pa Vb - Pb Va` term cancels when subtracting the equations
+/// of two hailstones, leaving one linear equation per pair. Positions are
+/// taken relative to the first hailstone to keep the elimination small.
+/// `None` if there are fewer than five hailstones, no unique solution, or
+/// the system overflows.
+fn solve_plane(hail: &[Hailstone], a: usize, b: usize) -> Option<[Rational; 4]> {
+    let first = hail.first()?;
+    let (matrix, rhs): (Vec<Vec<Rational>>, Vec<Rational>) = hail
+        .get(1..5)?
+        .iter()
+        .map(|h| {
+            let pa = h.pos[a].checked_sub(first.pos[a])?;
+            let pb = h.pos[b].checked_sub(first.pos[b])?;
+            let row = [
+                first.vel[b].checked_sub(h.vel[b])?,
+                h.vel[a].checked_sub(first.vel[a])?,
+                pb,
+                pa.checked_neg()?,
+            ]
+            .map(Rational::from)
+            .to_vec();
+            let value = pb
+                .checked_mul(h.vel[a])?
+                .checked_sub(pa.checked_mul(h.vel[b])?)?;
+            Some((row, Rational::from(value)))
+        })
+        .collect::<Option<Vec<(Vec<Rational>, Rational)>>>()?
+        .into_iter()
+        .unzip();
+
+    let x = math::solve_linear(matrix, rhs)?;
+    Some([
+        x[0].checked_add(Rational::from(first.pos[a]))?,
+        x[1].checked_add(Rational::from(first.pos[b]))?,
+        x[2],
+        x[3],
+    ])
+}
+
+/// Starting position of the rock, trying successive runs of five
+/// hailstones until both systems have a unique solution that agrees on x
+/// and really hits every hailstone, including those outside the run.
+fn throw_rock(hail: &[Hailstone]) -> Option<[i128; 3]> {
+    (0..hail.len().saturating_sub(4)).find_map(|i| {
+        let stones = &hail[i..i + 5];
+        let [x, y, vx, vy] = solve_plane(stones, 0, 1)?;
+        let [other_x, z, other_vx, vz] = solve_plane(stones, 0, 2)?;
+        if (x, vx) != (other_x, other_vx) {
+            return None;
+        }
+
+        let (pos, vel) = ([x, y, z], [vx, vy, vz]);
+        if !hail.iter().all(|h| h.is_hit_by(pos, vel) == Some(true)) {
+            return None;
+        }
+
+        Some([x.to_integer()?, y.to_integer()?, z.to_integer()?])
+    })
+}
+
+fn main() -> io::Result<()> {
+    println!("day24");
+
+    let hail = input::lines("./bin/day24/input")?
+        .map(|l| l.map(|l| Hailstone::new(&l)))
+        .collect::<io::Result<Vec<Hailstone>>>()?;
+
+    println!("hailstones {}", hail.len());
+    println!(
+        "intersections {}",
+        count_intersections(&hail, 200000000000000, 400000000000000)?
+    );
+
+    let rock = throw_rock(&hail).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "no rock hits every hailstone")
+    })?;
+    let sum = rock
+        .iter()
+        .try_fold(0i128, |sum, p| sum.checked_add(*p))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "rock position sum overflows"))?;
+    println!("rock {:?} sum {}", rock, sum);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 19, 13, 30 @ -2,  1, -2
+    // 18, 19, 22 @ -1, -1, -2
+    // 20, 25, 34 @ -2, -2, -4
+    // 12, 31, 28 @ -1, -2, -1
+    // 20, 19, 15 @  1, -5, -3
+    //
+    // 2 paths cross inside 7..27, the rock starts at 24, 13, 10
+
+    fn example() -> Vec<Hailstone> {
+        [
+            "19, 13, 30 @ -2,  1, -2",
+            "18, 19, 22 @ -1, -1, -2",
+            "20, 25, 34 @ -2, -2, -4",
+            "12, 31, 28 @ -1, -2, -1",
+            "20, 19, 15 @  1, -5, -3",
+        ]
+        .iter()
+        .map(|l| Hailstone::new(l))
+        .collect::<Vec<Hailstone>>()
+    }
+
+    #[test]
+    fn test_load() {
+        assert_eq!(
+            Hailstone::new("19, 13, 30 @ -2,  1, -2"),
+            Hailstone {
+                pos: [19, 13, 30],
+                vel: [-2, 1, -2]
+            }
+        );
+    }
+
+    fn frac(num: i128, den: i128) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn test_intersect_xy() {
+        let hail = example();
+        assert_eq!(
+            hail[0].intersect_xy(&hail[1]).unwrap(),
+            Some((frac(43, 3), frac(46, 3)))
+        );
+        assert_eq!(
+            hail[0].intersect_xy(&hail[3]).unwrap(),
+            Some((frac(31, 5), frac(97, 5)))
+        );
+        // parallel
+        assert_eq!(hail[1].intersect_xy(&hail[2]).unwrap(), None);
+        // crossed in the past for the first
+        assert_eq!(hail[0].intersect_xy(&hail[4]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_intersect_xy_overflow() {
+        let mut hail = example();
+        hail[0].pos[0] = i128::MIN;
+        assert!(hail[0].intersect_xy(&hail[1]).is_err());
+        hail[0].vel = [i128::MAX, 2, 0];
+        assert!(hail[1].intersect_xy(&hail[0]).is_err());
+        assert!(count_intersections(&hail, 7, 27).is_err());
+    }
+
+    #[test]
+    fn test_count_intersections() {
+        assert_eq!(count_intersections(&example(), 7, 27).unwrap(), 2);
+        // 43/3 and 46/3 sit between 14 and 16
+        assert_eq!(count_intersections(&example()[..2], 15, 15).unwrap(), 0);
+        assert_eq!(count_intersections(&example()[..2], 14, 16).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_is_hit_by() {
+        let r = |v: [i128; 3]| v.map(Rational::from);
+        let (pos, vel) = (r([24, 13, 10]), r([-3, 1, 2]));
+        assert!(example()
+            .iter()
+            .all(|h| h.is_hit_by(pos, vel) == Some(true)));
+        // right path, but reached at different times on each axis
+        assert_eq!(
+            example()[0].is_hit_by(r([24, 13, 10]), r([-3, 1, 1])),
+            Some(false)
+        );
+        // would have met it in the past
+        assert_eq!(
+            example()[0].is_hit_by(r([19, 13, 30]), r([-3, 1, 1])),
+            Some(true)
+        );
+        assert_eq!(
+            example()[0].is_hit_by(r([17, 14, 28]), r([-3, 1, 1])),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_solve_plane() {
+        let r = |n: i64| Rational::from(n);
+        assert_eq!(
+            solve_plane(&example(), 0, 1),
+            Some([r(24), r(13), r(-3), r(1)])
+        );
+        assert_eq!(
+            solve_plane(&example(), 0, 2),
+            Some([r(24), r(10), r(-3), r(2)])
+        );
+    }
+
+    #[test]
+    fn test_solve_plane_short() {
+        let hail = example();
+        assert_eq!(solve_plane(&hail[..4], 0, 1), None);
+        assert_eq!(solve_plane(&hail[..1], 0, 2), None);
+        assert_eq!(solve_plane(&[], 0, 1), None);
+    }
+
+    #[test]
+    fn test_solve_plane_overflow() {
+        let mut hail = example();
+        hail[1].pos[0] = i128::MAX;
+        hail[2].pos[0] = i128::MIN;
+        assert_eq!(solve_plane(&hail, 0, 1), None);
+    }
+
+    #[test]
+    fn test_throw_rock() {
+        assert_eq!(throw_rock(&example()), Some([24, 13, 10]));
+        assert_eq!(throw_rock(&example()[..4]), None);
+    }
+
+    #[test]
+    fn test_throw_rock_far_away() {
+        let offset = 300000000000000;
+        let mut hail = example();
+        for h in &mut hail {
+            h.pos = h.pos.map(|p| p + offset);
+        }
+        assert_eq!(
+            throw_rock(&hail),
+            Some([24 + offset, 13 + offset, 10 + offset])
+        );
+    }
+
+    #[test]
+    fn test_throw_rock_checks_other_hailstones() {
+        let mut hail = example();
+        hail.push(Hailstone {
+            pos: [0, 0, 0],
+            vel: [1, 1, 1],
+        });
+        assert_eq!(throw_rock(&hail), None);
+
+        // on the rock's path at t = 2
+        let mut hail = example();
+        hail.push(Hailstone {
+            pos: [16, 17, 12],
+            vel: [1, -1, 1],
+        });
+        assert_eq!(throw_rock(&hail), Some([24, 13, 10]));
+    }
+
+    #[test]
+    fn test_throw_rock_skips_overflowing_window() {
+        // met by the rock after 2^124 nanoseconds
+        let t = 1 << 124;
+        let mut hail = example();
+        hail.insert(
+            0,
+            Hailstone {
+                pos: [24 - 4 * t, 13, 10 + t],
+                vel: [1, 1, 1],
+            },
+        );
+        assert_eq!(solve_plane(&hail[..5], 0, 1), None);
+        assert_eq!(throw_rock(&hail), Some([24, 13, 10]));
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, io};
+
+use aoc23::{answer::Answer, graph::Graph, input};
+
+fn load(lines: &[String]) -> Graph<String, ()> {
+    let mut graph = Graph::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for line in lines {
+        let (name, others) = line.split_once(": ").unwrap();
+        let mut node = |name: &str| {
+            *index
+                .entry(name.to_string())
+                .or_insert_with(|| graph.add_node(name.to_string()))
+        };
+
+        let from = node(name);
+        let others = others.split(' ').map(&mut node).collect::<Vec<usize>>();
+        for to in others {
+            graph.add_undirected_edge(from, to, ());
+        }
+    }
+
+    graph
+}
+
+/// Sizes of the two groups left after cutting exactly `wires` wires. The
+/// first component is always on one side, so some other component has to be
+/// on the far side of the cut.
+fn split_groups(graph: &Graph<String, ()>, wires: usize) -> Option<(usize, usize)> {
+    (1..graph.len()).find_map(|sink| {
+        let (cut, side) = graph.min_cut(0, sink, wires)?;
+        if cut != wires {
+            return None;
+        }
+
+        let first = side.iter().filter(|s| **s).count();
+        Some((first, graph.len() - first))
+    })
+}
+
+fn main() -> io::Result<()> {
+    println!("day25");
+
+    let lines = input::lines("./bin/day25/input")?.collect::<io::Result<Vec<String>>>()?;
+    let graph = load(&lines);
+
+    println!("components {}", graph.len());
+
+    let (a, b) = split_groups(&graph, 3).unwrap();
+    let product = Answer::product([a, b], "group product")?;
+    println!("groups {} {} product {}", a, b, product);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cutting hfx/pzl, bvb/cmg and nvd/jqt leaves groups of 9 and 6
+
+    fn example() -> Graph<String, ()> {
+        load(
+            &[
+                "jqt: rhn xhk nvd",
+                "rsh: frs pzl lsr",
+                "xhk: hfx",
+                "cmg: qnr nvd lhk bvb",
+                "rhn: xhk bvb hfx",
+                "bvb: xhk hfx",
+                "pzl: lsr hfx nvd",
+                "qnr: nvd",
+                "ntq: jqt hfx bvb xhk",
+                "nvd: lhk",
+                "lsr: lhk",
+                "rzs: qnr cmg lsr rsh",
+                "frs: qnr lhk lsr",
+            ]
+            .iter()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>(),
+        )
+    }
+
+    #[test]
+    fn test_load() {
+        let graph = example();
+        assert_eq!(graph.len(), 15);
+        assert_eq!(graph.edge_count(), 66);
+
+        let jqt = graph.find(|n| n == "jqt").unwrap();
+        let mut neighbours = graph
+            .edges(jqt)
+            .iter()
+            .map(|(to, _)| graph.node(*to).as_str())
+            .collect::<Vec<&str>>();
+        neighbours.sort_unstable();
+        assert_eq!(neighbours, vec!["ntq", "nvd", "rhn", "xhk"]);
+    }
+
+    #[test]
+    fn test_split_groups() {
+        let (a, b) = split_groups(&example(), 3).unwrap();
+        assert_eq!(a * b, 54);
+        assert!((a, b) == (9, 6) || (a, b) == (6, 9));
+    }
+
+    #[test]
+    fn test_split_groups_no_cut() {
+        assert_eq!(split_groups(&example(), 2), None);
+    }
+}
@@ -4,47 +4,27 @@ use std::{
     path::Path,
 };
 
-struct Grid {
-    rows: Vec<Vec<char>>,
-}
-
-impl Grid {
-    fn new() -> Grid {
-        Grid { rows: Vec::new() }
-    }
-
-    fn add_row(&mut self, row: &String) {
-        self.rows.push(row.chars().collect::<Vec<char>>());
-    }
-
-    fn verify(&self) -> bool {
-        let first_len = &self.rows[0].len();
-        for row in &self.rows {
-            if &row.len() != first_len {
-                return false;
-            }
-        }
+use aoc23::{
+    answer::{Answer, AnswerError},
+    grid::Grid,
+};
 
-        true
-    }
+/// A number in the schematic: its value, start position and digit count.
+type Number = (u64, usize, usize, usize);
 
-    fn get(&self, x: usize, y: usize) -> char {
-        self.rows[y][x]
-    }
+pub(crate) trait Schematic {
+    fn get_protected(&self, x: usize, y: usize, add_x: i32, add_y: i32) -> char;
+    fn is_symbol_adjacent(&self, x: usize, y: usize, len: usize) -> bool;
+    fn find_next_num_in_row(&self, x: usize, y: usize) -> Result<Option<Number>, AnswerError>;
+    fn find_next_num(&self, x: usize, y: usize) -> Result<Option<Number>, AnswerError>;
+    fn find_all_adjacent_numbers(&self) -> Result<Vec<u64>, AnswerError>;
+}
 
+impl Schematic for Grid<char> {
     fn get_protected(&self, x: usize, y: usize, add_x: i32, add_y: i32) -> char {
-        let new_x = x as i32 + add_x;
-        let new_y = y as i32 + add_y;
-
-        if new_x < 0
-            || new_y < 0
-            || new_x as usize >= self.rows[y].len()
-            || new_y as usize >= self.rows.len()
-        {
-            return '.';
-        }
-
-        self.rows[(y as i32 + add_y) as usize][(x as i32 + add_x) as usize]
+        *self
+            .get_offset(x, y, add_x as i64, add_y as i64)
+            .unwrap_or(&'.')
     }
 
     fn is_symbol_adjacent(&self, x: usize, y: usize, len: usize) -> bool {
@@ -66,63 +46,58 @@ impl Grid {
         symbol_found
     }
 
-    fn find_next_num_in_row(&self, x: usize, y: usize) -> Option<(u32, usize, usize, usize)> {
+    fn find_next_num_in_row(&self, x: usize, y: usize) -> Result<Option<Number>, AnswerError> {
         let mut num: Vec<char> = Vec::new();
         let mut pos: Option<(usize, usize)> = None;
 
-        if y >= self.rows.len() {
-            return None;
+        if y >= self.height() {
+            return Ok(None);
         }
 
-        for x in x..self.rows[y].len() {
-            let n = self.get(x, y);
-            if num.len() > 0 && !n.is_numeric() {
+        for x in x..self.row(y).len() {
+            let n = *self.get(x, y);
+            if !num.is_empty() && !n.is_ascii_digit() {
                 break;
             }
 
-            if n.is_numeric() {
+            if n.is_ascii_digit() {
                 num.push(n);
-                if pos == None {
+                if pos.is_none() {
                     pos = Some((x, y));
                 }
             }
         }
 
-        if num.len() > 0 {
-            Some((
-                (&num)
-                    .into_iter()
-                    .collect::<String>()
-                    .parse::<u32>()
-                    .unwrap(),
-                pos.unwrap().0,
-                pos.unwrap().1,
-                num.len(),
-            ))
+        if let Some((x, y)) = pos {
+            let value = Answer::parse_digits(
+                &num.iter().collect::<String>(),
+                format_args!("number at {},{}", x, y),
+            )?;
+            Ok(Some((value.value(), x, y, num.len())))
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn find_next_num(&self, x: usize, y: usize) -> Option<(u32, usize, usize, usize)> {
-        if let Some(val) = self.find_next_num_in_row(x, y) {
-            return Some(val);
+    fn find_next_num(&self, x: usize, y: usize) -> Result<Option<Number>, AnswerError> {
+        if let Some(val) = self.find_next_num_in_row(x, y)? {
+            return Ok(Some(val));
         }
 
-        for y in y + 1..self.rows.len() {
-            if let Some(val) = self.find_next_num_in_row(0, y) {
-                return Some(val);
+        for y in y + 1..self.height() {
+            if let Some(val) = self.find_next_num_in_row(0, y)? {
+                return Ok(Some(val));
             }
         }
 
-        None
+        Ok(None)
     }
 
-    fn find_all_adjacent_numbers(&self) -> Vec<u32> {
+    fn find_all_adjacent_numbers(&self) -> Result<Vec<u64>, AnswerError> {
         let mut next_x: usize = 0;
         let mut next_y: usize = 0;
-        let mut nums: Vec<u32> = Vec::new();
-        while let Some((num, x, y, len)) = self.find_next_num(next_x, next_y) {
+        let mut nums: Vec<u64> = Vec::new();
+        while let Some((num, x, y, len)) = self.find_next_num(next_x, next_y)? {
             if self.is_symbol_adjacent(x, y, len) {
                 nums.push(num);
             }
@@ -131,7 +106,7 @@ impl Grid {
             next_y = y;
         }
 
-        nums
+        Ok(nums)
     }
 }
 
@@ -146,18 +121,26 @@ fn main() -> io::Result<()> {
     for line in lines {
         grid.add_row(&line?);
     }
-    grid.verify();
+    grid.ensure_rectangular()?;
 
-    let nums = grid.find_all_adjacent_numbers();
-    let sum = nums.iter().fold(0, |acc, n| acc + n);
+    let nums = grid.find_all_adjacent_numbers()?;
+    let sum = Answer::sum(nums, "sum of part numbers")?;
     println!("sum {}", sum);
 
     Ok(())
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_to_owned, clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use aoc23::{
+        differential::{Differential, Shrink},
+        generate::{self, Day3Config},
+        random::Rng,
+        reference,
+    };
+    use std::str;
 
     // 467..114..
     // ...*......
@@ -208,7 +191,7 @@ mod tests {
         grid.add_row(&".1.".to_string());
         grid.add_row(&"..$".to_string());
         grid.verify();
-        assert!(grid.is_symbol_adjacent(1, 1, 1));
+        assert_eq!(grid.is_symbol_adjacent(1, 1, 1), true);
     }
 
     #[test]
@@ -228,7 +211,7 @@ mod tests {
         grid.add_row(&".12.".to_string());
         grid.add_row(&"..$.".to_string());
         grid.verify();
-        assert!(grid.is_symbol_adjacent(1, 1, 2));
+        assert_eq!(grid.is_symbol_adjacent(1, 1, 2), true);
     }
 
     #[test]
@@ -238,7 +221,7 @@ mod tests {
         grid.add_row(&".123.".to_string());
         grid.add_row(&"....$".to_string());
         grid.verify();
-        assert!(grid.is_symbol_adjacent(1, 1, 3));
+        assert_eq!(grid.is_symbol_adjacent(1, 1, 3), true);
     }
 
     #[test]
@@ -248,7 +231,7 @@ mod tests {
         grid.add_row(&".123.".to_string());
         grid.add_row(&".....".to_string());
         grid.verify();
-        assert!(grid.is_symbol_adjacent(1, 1, 3));
+        assert_eq!(grid.is_symbol_adjacent(1, 1, 3), true);
     }
 
     #[test]
@@ -258,7 +241,7 @@ mod tests {
         grid.add_row(&"$123.".to_string());
         grid.add_row(&".....".to_string());
         grid.verify();
-        assert!(grid.is_symbol_adjacent(1, 1, 3));
+        assert_eq!(grid.is_symbol_adjacent(1, 1, 3), true);
     }
 
     #[test]
@@ -268,15 +251,15 @@ mod tests {
         grid.add_row(&".123*".to_string());
         grid.add_row(&".....".to_string());
         grid.verify();
-        assert!(grid.is_symbol_adjacent(1, 1, 3));
+        assert_eq!(grid.is_symbol_adjacent(1, 1, 3), true);
     }
 
     #[test]
     fn test_find_next_num_single_row() {
         let mut grid = Grid::new();
         grid.add_row(&"467..11..".to_string());
-        assert_eq!(grid.find_next_num(0, 0), Some((467, 0, 0, 3)));
-        assert_eq!(grid.find_next_num(3, 0), Some((11, 5, 0, 2)));
+        assert_eq!(grid.find_next_num(0, 0), Ok(Some((467, 0, 0, 3))));
+        assert_eq!(grid.find_next_num(3, 0), Ok(Some((11, 5, 0, 2))));
     }
 
     #[test]
@@ -286,11 +269,11 @@ mod tests {
         grid.add_row(&"...*......".to_string());
         grid.add_row(&"..35..633.".to_string());
         grid.add_row(&"......#...".to_string());
-        assert_eq!(grid.find_next_num(0, 0), Some((467, 0, 0, 3)));
-        assert_eq!(grid.find_next_num(3, 0), Some((114, 5, 0, 3)));
-        assert_eq!(grid.find_next_num(8, 0), Some((35, 2, 2, 2)));
-        assert_eq!(grid.find_next_num(4, 2), Some((633, 6, 2, 3)));
-        assert_eq!(grid.find_next_num(9, 2), None);
+        assert_eq!(grid.find_next_num(0, 0), Ok(Some((467, 0, 0, 3))));
+        assert_eq!(grid.find_next_num(3, 0), Ok(Some((114, 5, 0, 3))));
+        assert_eq!(grid.find_next_num(8, 0), Ok(Some((35, 2, 2, 2))));
+        assert_eq!(grid.find_next_num(4, 2), Ok(Some((633, 6, 2, 3))));
+        assert_eq!(grid.find_next_num(9, 2), Ok(None));
     }
 
     #[test]
@@ -298,10 +281,10 @@ mod tests {
         let mut grid = Grid::new();
         grid.add_row(&"467....114".to_string());
         grid.add_row(&"...23.....".to_string());
-        assert_eq!(grid.find_next_num(0, 0), Some((467, 0, 0, 3)));
-        assert_eq!(grid.find_next_num(3, 0), Some((114, 7, 0, 3)));
-        assert_eq!(grid.find_next_num(10, 0), Some((23, 3, 1, 2)));
-        assert_eq!(grid.find_next_num(9, 2), None);
+        assert_eq!(grid.find_next_num(0, 0), Ok(Some((467, 0, 0, 3))));
+        assert_eq!(grid.find_next_num(3, 0), Ok(Some((114, 7, 0, 3))));
+        assert_eq!(grid.find_next_num(10, 0), Ok(Some((23, 3, 1, 2))));
+        assert_eq!(grid.find_next_num(9, 2), Ok(None));
     }
 
     #[test]
@@ -332,7 +315,7 @@ mod tests {
         grid.add_row(&".664.598..".to_string());
         assert!(grid.verify());
 
-        let nums = grid.find_all_adjacent_numbers();
+        let nums = grid.find_all_adjacent_numbers().unwrap();
         assert_eq!(nums[0], 467);
         assert_eq!(nums[1], 35);
         assert_eq!(nums[2], 633);
@@ -342,7 +325,127 @@ mod tests {
         assert_eq!(nums[6], 664);
         assert_eq!(nums[7], 598);
 
-        let sum = nums.iter().fold(0, |acc, n| acc + n);
-        assert_eq!(sum, 4361);
+        let sum = Answer::sum(nums, "sum").unwrap();
+        assert_eq!(sum.value(), 4361);
+    }
+
+    #[test]
+    fn test_long_digit_run() {
+        let mut grid = Grid::new();
+        grid.add_row(&"*18446744073709551615.".to_string());
+        grid.add_row(&".18446744073709551616*".to_string());
+        assert_eq!(grid.find_next_num(0, 0), Ok(Some((u64::MAX, 1, 0, 20))));
+        assert_eq!(
+            grid.find_next_num(21, 0),
+            Err(AnswerError::Overflow("number at 1,1".to_string()))
+        );
+        assert!(grid.find_all_adjacent_numbers().is_err());
+    }
+
+    #[test]
+    fn test_neighbouring_digits_count_as_symbols() {
+        // Anything but `.` counts, including the digits of another number.
+        let grid = Grid::from_lines(&[".3", "9."]);
+        assert_eq!(grid.is_symbol_adjacent(1, 0, 1), true);
+        assert_eq!(grid.is_symbol_adjacent(0, 1, 1), true);
+        assert_eq!(grid.find_all_adjacent_numbers(), Ok(vec![3, 9]));
+    }
+
+    /// Schematic rows, shrunk by dropping whole rows or columns or blanking
+    /// single cells so the grid stays rectangular.
+    #[derive(Debug, Clone)]
+    struct Rows(Vec<String>);
+
+    impl Shrink for Rows {
+        fn shrink(&self) -> Vec<Rows> {
+            let rows = &self.0;
+            let width = rows.first().map_or(0, |r| r.len());
+            let mut smaller = Vec::new();
+            for y in 0..rows.len() {
+                let mut r = rows.clone();
+                r.remove(y);
+                smaller.push(Rows(r));
+            }
+            for x in 0..width {
+                smaller.push(Rows(
+                    rows.iter()
+                        .map(|r| format!("{}{}", &r[..x], &r[x + 1..]))
+                        .collect(),
+                ));
+            }
+            for (y, row) in rows.iter().enumerate() {
+                for (x, c) in row.char_indices().filter(|&(_, c)| c != '.') {
+                    let mut r = rows.clone();
+                    r[y].replace_range(x..x + c.len_utf8(), ".");
+                    smaller.push(Rows(r));
+                }
+            }
+            smaller
+        }
+    }
+
+    fn random_rows(rng: &mut Rng) -> Rows {
+        let config = Day3Config {
+            width: rng.range(1..12),
+            height: rng.range(1..8),
+            number_percent: 30,
+            symbol_percent: 20,
+            ..Day3Config::default()
+        };
+        let generated = generate::day3(rng.next_u64(), &config).unwrap();
+        Rows(generated.input.lines().map(str::to_string).collect())
+    }
+
+    #[test]
+    fn test_adjacent_numbers_agree_with_reference() {
+        Differential::new()
+            .register("reference", |rows: &Rows| {
+                reference::part_numbers(&rows.0.join("\n")).unwrap()
+            })
+            .register("find_all_adjacent_numbers", |rows: &Rows| {
+                Grid::from_lines(&rows.0)
+                    .find_all_adjacent_numbers()
+                    .unwrap()
+            })
+            .assume(|rows: &Rows| !rows.0.is_empty() && !rows.0[0].is_empty())
+            .assert_agree(48, 1000, random_rows);
+    }
+
+    /// Checks a fuzzer input for the schematic: no grid may panic, ragged or
+    /// not, an ASCII grid must match the reference, and CRLF line endings must
+    /// not change the part numbers.
+    fn check(data: &[u8]) {
+        let Ok(text) = str::from_utf8(data) else {
+            return;
+        };
+
+        let grid = Grid::from_lines(&text.lines().collect::<Vec<&str>>());
+        let nums = grid.find_all_adjacent_numbers();
+        if text.is_ascii() {
+            if let Ok(nums) = &nums {
+                assert_eq!(reference::part_numbers(text).as_ref(), Ok(nums));
+            }
+        }
+
+        // A lone carriage return is a symbol, so only convert plain line
+        // endings.
+        if !text.contains('\r') {
+            let crlf = text.replace('\n', "\r\n");
+            let crlf_grid = Grid::from_lines(&crlf.lines().collect::<Vec<&str>>());
+            assert_eq!(crlf_grid.find_all_adjacent_numbers(), nums);
+        }
+    }
+
+    // Ragged rows and non-ASCII numerals, which used to panic when looking
+    // past the end of a shorter row or parsing the digits.
+    const REPRODUCERS: [&str; 6] = ["1\n", "..\n1", "1\n..*", ".1.\n*", "½1*", "٣\n1"];
+
+    #[test]
+    fn test_fuzz_reproducers() {
+        for input in REPRODUCERS {
+            check(input.as_bytes());
+        }
+        let grid = Grid::from_lines(&["٣", "1"]);
+        assert_eq!(grid.find_all_adjacent_numbers(), Ok(vec![1]));
     }
 }
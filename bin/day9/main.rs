@@ -0,0 +1,127 @@
+use std::io;
+
+use aoc23::{input, math::DifferenceTable};
+
+fn parse_sequence(line: &str) -> Option<Vec<i64>> {
+    line.split_whitespace()
+        .map(|n| n.parse::<i64>().ok())
+        .collect::<Option<Vec<i64>>>()
+}
+
+/// Sums of the next and of the previous values of every sequence, printing
+/// each line with its table. Fails naming the first line that is not a
+/// sequence of numbers or overflows.
+fn extrapolate_all(lines: impl Iterator<Item = io::Result<String>>) -> io::Result<(i64, i64)> {
+    let mut next_sum: i64 = 0;
+    let mut prev_sum: i64 = 0;
+    for (i, l) in lines.enumerate() {
+        let line = l?;
+        let error = |msg: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} in line {}", msg, i + 1),
+            )
+        };
+
+        let values = parse_sequence(&line).ok_or_else(|| error("invalid number"))?;
+        let table = DifferenceTable::new(&values)
+            .ok_or_else(|| error("empty sequence or overflowing difference"))?;
+        let next = table
+            .extrapolate_forward()
+            .ok_or_else(|| error("overflow extrapolating"))?;
+        let prev = table
+            .extrapolate_backward()
+            .ok_or_else(|| error("overflow extrapolating"))?;
+
+        println!("{} {} {}", line, prev, next);
+        print!("{}", table);
+
+        next_sum = next_sum
+            .checked_add(next)
+            .ok_or_else(|| error("overflow summing"))?;
+        prev_sum = prev_sum
+            .checked_add(prev)
+            .ok_or_else(|| error("overflow summing"))?;
+    }
+
+    Ok((next_sum, prev_sum))
+}
+
+fn main() -> io::Result<()> {
+    println!("day9");
+
+    let (next_sum, prev_sum) = extrapolate_all(input::lines("./bin/day9/input")?)?;
+
+    println!("sum of next values {}", next_sum);
+    println!("sum of previous values {}", prev_sum);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 3 6 9 12 15
+    // 1 3 6 10 15 21
+    // 10 13 16 21 30 45
+    //
+    // Next values sum to 114, previous values sum to 2
+
+    #[test]
+    fn test_parse_sequence() {
+        assert_eq!(
+            parse_sequence("0 3 6 9 12 15"),
+            Some(vec![0, 3, 6, 9, 12, 15])
+        );
+        assert_eq!(parse_sequence("-4 -1 2"), Some(vec![-4, -1, 2]));
+        assert_eq!(parse_sequence("1 x 2"), None);
+    }
+
+    #[test]
+    fn test_example() {
+        let tables = ["0 3 6 9 12 15", "1 3 6 10 15 21", "10 13 16 21 30 45"]
+            .iter()
+            .map(|l| DifferenceTable::new(&parse_sequence(l).unwrap()).unwrap())
+            .collect::<Vec<DifferenceTable>>();
+
+        let next = tables
+            .iter()
+            .map(|t| t.extrapolate_forward().unwrap())
+            .sum::<i64>();
+        let prev = tables
+            .iter()
+            .map(|t| t.extrapolate_backward().unwrap())
+            .sum::<i64>();
+
+        assert_eq!(next, 114);
+        assert_eq!(prev, 2);
+    }
+    fn extrapolate_lines(lines: &[&str]) -> io::Result<(i64, i64)> {
+        extrapolate_all(lines.iter().map(|l| Ok(l.to_string())))
+    }
+
+    #[test]
+    fn test_extrapolate_all() {
+        assert_eq!(
+            extrapolate_lines(&["0 3 6 9 12 15", "1 3 6 10 15 21", "10 13 16 21 30 45"]).unwrap(),
+            (114, 2)
+        );
+    }
+
+    #[test]
+    fn test_extrapolate_all_errors() {
+        let error = |lines: &[&str]| extrapolate_lines(lines).unwrap_err().to_string();
+        assert_eq!(error(&["1 2", "1 x"]), "invalid number in line 2");
+        assert_eq!(
+            error(&["1 2", ""]),
+            "empty sequence or overflowing difference in line 2"
+        );
+        assert_eq!(
+            error(&[&format!("0 {}", i64::MAX)]),
+            "overflow extrapolating in line 1"
+        );
+        let big = format!("{0} {0}", i64::MAX / 2 + 1);
+        assert_eq!(error(&["1", &big, &big]), "overflow summing in line 3");
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Write},
+    str::FromStr,
+};
+
+use aoc23::generate::{self, Day1Config, Day2Config, Day3Config, Generated};
+
+const USAGE: &str = "usage: generate <day1|day2|day3> [--seed N] [--out PATH] [options]
+  day1: --lines N --max-pieces N --overlap PERCENT
+  day2: --games N --max-sets N --max-cubes N
+  day3: --width N --height N --numbers PERCENT --symbols PERCENT --symbol-set CHARS --max-digits N";
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("{}\n{}", msg, USAGE))
+}
+
+/// `--name value` pairs following the day.
+struct Options(HashMap<String, String>);
+
+impl Options {
+    fn parse(args: &[String]) -> io::Result<Options> {
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| invalid(format!("unexpected argument {}", arg)))?;
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("missing value for --{}", name)))?;
+            options.insert(name.to_string(), value.clone());
+        }
+
+        Ok(Options(options))
+    }
+
+    /// Removes and parses `--name`, or returns `default` if it was not given.
+    fn take<T: FromStr>(&mut self, name: &str, default: T) -> io::Result<T> {
+        match self.0.remove(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| invalid(format!("invalid value for --{}: {}", name, value))),
+            None => Ok(default),
+        }
+    }
+
+    /// Fails on any option no day asked for.
+    fn finish(self) -> io::Result<()> {
+        match self.0.keys().next() {
+            Some(name) => Err(invalid(format!("unknown option --{}", name))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn generate(day: &str, options: &mut Options) -> io::Result<Generated> {
+    let seed = options.take("seed", 1)?;
+    match day {
+        "day1" => {
+            let d = Day1Config::default();
+            let config = Day1Config {
+                lines: options.take("lines", d.lines)?,
+                max_pieces: options.take("max-pieces", d.max_pieces)?,
+                overlap_percent: options.take("overlap", d.overlap_percent)?,
+            };
+            Ok(generate::day1(seed, &config))
+        }
+        "day2" => {
+            let d = Day2Config::default();
+            let config = Day2Config {
+                games: options.take("games", d.games)?,
+                max_sets: options.take("max-sets", d.max_sets)?,
+                max_cubes: options.take("max-cubes", d.max_cubes)?,
+            };
+            Ok(generate::day2(seed, &config)?)
+        }
+        "day3" => {
+            let d = Day3Config::default();
+            let symbols = options.take("symbol-set", d.symbols.iter().collect::<String>())?;
+            let config = Day3Config {
+                width: options.take("width", d.width)?,
+                height: options.take("height", d.height)?,
+                number_percent: options.take("numbers", d.number_percent)?,
+                symbol_percent: options.take("symbols", d.symbol_percent)?,
+                symbols: symbols.chars().collect(),
+                max_digits: options.take("max-digits", d.max_digits)?,
+            };
+            Ok(generate::day3(seed, &config)?)
+        }
+        _ => Err(invalid(format!("unknown day {}", day))),
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let day = args
+        .first()
+        .ok_or_else(|| invalid("missing day".to_string()))?;
+    let mut options = Options::parse(&args[1..])?;
+    let out = options.take("out", String::new())?;
+    let generated = generate(day, &mut options)?;
+    options.finish()?;
+
+    if out.is_empty() {
+        io::stdout().write_all(generated.input.as_bytes())?;
+    } else {
+        fs::write(&out, &generated.input)?;
+    }
+
+    for (label, answer) in generated.answers {
+        eprintln!("{} {}", label, answer);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> io::Result<Options> {
+        Options::parse(&args.iter().map(|a| a.to_string()).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn test_options() {
+        let mut opts = options(&["--seed", "9", "--lines", "3"]).unwrap();
+        let generated = generate("day1", &mut opts).unwrap();
+        assert!(opts.finish().is_ok());
+        assert_eq!(generated.input.lines().count(), 3);
+        assert_eq!(
+            generated,
+            generate::day1(
+                9,
+                &Day1Config {
+                    lines: 3,
+                    ..Day1Config::default()
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert!(options(&["--seed"]).is_err());
+        assert!(options(&["seed", "1"]).is_err());
+        assert!(generate("day1", &mut options(&["--lines", "x"]).unwrap()).is_err());
+        assert!(generate("day4", &mut options(&[]).unwrap()).is_err());
+
+        let mut opts = options(&["--max-cubes", "4294967295"]).unwrap();
+        assert!(generate("day2", &mut opts).is_err());
+        let mut opts = options(&["--max-digits", "30"]).unwrap();
+        assert!(generate("day3", &mut opts).is_err());
+
+        let mut opts = options(&["--games", "3"]).unwrap();
+        generate("day1", &mut opts).unwrap();
+        assert!(opts.finish().is_err());
+    }
+}
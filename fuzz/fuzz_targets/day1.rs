@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../bin/day1/main.rs"]
+mod day1;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        for line in text.lines() {
+            for reverse in [false, true] {
+                day1::find_first_number(line, reverse);
+                day1::find_first_word_number(line, reverse);
+                day1::find_number_by_word_and_digit(line, reverse);
+            }
+            day1::calibration_value(line);
+            day1::explain(line, true);
+        }
+    }
+
+    let _ = day1::parallel_sum(data, 3);
+});
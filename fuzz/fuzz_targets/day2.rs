@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../bin/day2/main.rs"]
+mod day2;
+
+fuzz_target!(|line: &str| {
+    if let Ok(game) = day2::Game::new(line.to_string()) {
+        // A parsed game must parse again from its canonical form, unless a
+        // set of only ignored colours prints as nothing.
+        let (_, sets) = line.split_once(':').unwrap();
+        if sets
+            .split(';')
+            .all(|s| !day2::Set::new(s).unwrap().to_string().is_empty())
+        {
+            day2::Game::new(game.to_string()).unwrap();
+        }
+    }
+
+    for set in line.split(';') {
+        let _ = day2::Set::new(set);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use aoc23::grid::Grid;
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../bin/day3/main.rs"]
+mod day3;
+
+use day3::Schematic;
+
+fuzz_target!(|text: &str| {
+    let grid = Grid::<char>::from_lines(&text.lines().collect::<Vec<&str>>());
+    let _ = grid.ensure_rectangular();
+    let _ = grid.find_all_adjacent_numbers();
+});
@@ -0,0 +1,183 @@
+use std::{error, fmt, io};
+
+/// Why a puzzle answer could not be computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerError {
+    /// The answer no longer fits in 64 bits.
+    Overflow(String),
+    /// A digit run or field was not a number.
+    NotANumber(String),
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerError::Overflow(context) => write!(f, "answer overflow in {}", context),
+            AnswerError::NotANumber(context) => write!(f, "not a number in {}", context),
+        }
+    }
+}
+
+impl error::Error for AnswerError {}
+
+impl From<AnswerError> for io::Error {
+    fn from(err: AnswerError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// A puzzle answer accumulated with checked 64-bit arithmetic. Every
+/// operation takes a context describing what was being computed, which ends
+/// up in the error if the answer overflows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Answer(u64);
+
+impl Answer {
+    pub const ZERO: Answer = Answer(0);
+    pub const ONE: Answer = Answer(1);
+
+    pub fn new(value: u64) -> Answer {
+        Answer(value)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn checked_add(
+        self,
+        rhs: impl Into<Answer>,
+        context: impl fmt::Display,
+    ) -> Result<Answer, AnswerError> {
+        self.0
+            .checked_add(rhs.into().0)
+            .map(Answer)
+            .ok_or_else(|| AnswerError::Overflow(context.to_string()))
+    }
+
+    pub fn checked_mul(
+        self,
+        rhs: impl Into<Answer>,
+        context: impl fmt::Display,
+    ) -> Result<Answer, AnswerError> {
+        self.0
+            .checked_mul(rhs.into().0)
+            .map(Answer)
+            .ok_or_else(|| AnswerError::Overflow(context.to_string()))
+    }
+
+    /// Sum of all `values`, failing on the first overflow.
+    pub fn sum<I>(values: I, context: impl fmt::Display) -> Result<Answer, AnswerError>
+    where
+        I: IntoIterator,
+        I::Item: Into<Answer>,
+    {
+        values
+            .into_iter()
+            .try_fold(Answer::ZERO, |acc, v| acc.checked_add(v, &context))
+    }
+
+    /// Product of all `values`, failing on the first overflow.
+    pub fn product<I>(values: I, context: impl fmt::Display) -> Result<Answer, AnswerError>
+    where
+        I: IntoIterator,
+        I::Item: Into<Answer>,
+    {
+        values
+            .into_iter()
+            .try_fold(Answer::ONE, |acc, v| acc.checked_mul(v, &context))
+    }
+
+    /// Parses a run of ASCII digits, however long, without wrapping.
+    pub fn parse_digits(digits: &str, context: impl fmt::Display) -> Result<Answer, AnswerError> {
+        if digits.is_empty() {
+            return Err(AnswerError::NotANumber(context.to_string()));
+        }
+
+        digits.chars().try_fold(Answer::ZERO, |acc, c| {
+            let digit = c
+                .to_digit(10)
+                .ok_or_else(|| AnswerError::NotANumber(context.to_string()))?;
+            acc.checked_mul(10u64, &context)?
+                .checked_add(digit, &context)
+        })
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Answer {
+        Answer(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Answer {
+        Answer(u64::from(value))
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Answer {
+        Answer(value as u64)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_and_product() {
+        assert_eq!(Answer::sum([1u32, 2, 3], "sum").unwrap().value(), 6);
+        assert_eq!(Answer::product([4u32, 2, 6], "power").unwrap().value(), 48);
+        assert_eq!(Answer::sum(Vec::<u64>::new(), "sum").unwrap(), Answer::ZERO);
+        assert_eq!(
+            Answer::product(Vec::<u64>::new(), "power").unwrap(),
+            Answer::ONE
+        );
+    }
+
+    #[test]
+    fn test_overflow_reports_context() {
+        let err = Answer::new(u64::MAX)
+            .checked_add(1u32, "line 7")
+            .unwrap_err();
+        assert_eq!(err, AnswerError::Overflow("line 7".to_string()));
+        assert_eq!(err.to_string(), "answer overflow in line 7");
+
+        let err = Answer::product([u32::MAX; 3], "game 1 power").unwrap_err();
+        assert_eq!(err.to_string(), "answer overflow in game 1 power");
+
+        let err = io::Error::from(Answer::sum([u64::MAX, 1], "ids").unwrap_err());
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_parse_digits() {
+        assert_eq!(Answer::parse_digits("467", "x").unwrap().value(), 467);
+        assert_eq!(
+            Answer::parse_digits("18446744073709551615", "x")
+                .unwrap()
+                .value(),
+            u64::MAX
+        );
+        assert_eq!(
+            Answer::parse_digits("18446744073709551616", "x"),
+            Err(AnswerError::Overflow("x".to_string()))
+        );
+        assert_eq!(
+            Answer::parse_digits("", "x"),
+            Err(AnswerError::NotANumber("x".to_string()))
+        );
+        assert_eq!(
+            Answer::parse_digits("4a", "x"),
+            Err(AnswerError::NotANumber("x".to_string()))
+        );
+    }
+}
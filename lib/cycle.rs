@@ -0,0 +1,82 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Start and length of the cycle reached by repeatedly applying `step` to
+/// `initial`, or `None` if no state repeats within `limit` steps.
+pub fn find_cycle<S, F>(initial: S, limit: usize, mut step: F) -> Option<(usize, usize)>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for i in 0..=limit {
+        if let Some(start) = seen.insert(state.clone(), i) {
+            return Some((start, i - start));
+        }
+
+        state = step(&state);
+    }
+
+    None
+}
+
+/// Applies `step` to `initial` `n` times. As soon as a state repeats, the
+/// remaining whole cycles are skipped, so `n` can be far larger than the
+/// number of distinct states.
+pub fn fast_forward<S, F>(initial: S, n: usize, mut step: F) -> S
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    for i in 0..n {
+        if let Some(start) = seen.insert(state.clone(), i) {
+            let len = i - start;
+            return history.swap_remove(start + (n - start) % len);
+        }
+
+        history.push(state.clone());
+        state = step(&state);
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1 -> 4 -> ...
+    fn collatz(n: &u64) -> u64 {
+        if n.is_multiple_of(2) {
+            n / 2
+        } else {
+            3 * n + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(find_cycle(3, 100, collatz), Some((5, 3)));
+        assert_eq!(find_cycle(3, 4, collatz), None);
+        assert_eq!(find_cycle(0u64, 1, |n| n + 1), None);
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let mut state = 3;
+        for n in 0..50 {
+            assert_eq!(fast_forward(3, n, collatz), state);
+            state = collatz(&state);
+        }
+    }
+
+    #[test]
+    fn test_fast_forward_large() {
+        assert_eq!(fast_forward(3, 1_000_000_000, collatz), 1);
+        assert_eq!(fast_forward(3, 1_000_000_001, collatz), 4);
+        assert_eq!(fast_forward(0u64, 1_000_000_000, |n| (n + 1) % 7), 6);
+    }
+}
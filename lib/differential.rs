@@ -0,0 +1,289 @@
+use std::{collections::HashSet, fmt};
+
+use crate::random::Rng;
+
+/// Inputs that can propose smaller versions of themselves, simplest first,
+/// so a failing case can be reduced to a minimal one.
+pub trait Shrink: Clone {
+    fn shrink(&self) -> Vec<Self>;
+}
+
+impl Shrink for String {
+    /// Drops the first or second half, then each single character, skipping
+    /// candidates already proposed.
+    fn shrink(&self) -> Vec<String> {
+        let chars = self.chars().collect::<Vec<char>>();
+        let mut smaller: Vec<String> = Vec::new();
+        if chars.len() > 1 {
+            let half = chars.len() / 2;
+            smaller.push(chars[half..].iter().collect());
+            smaller.push(chars[..half].iter().collect());
+        }
+        for i in 0..chars.len() {
+            smaller.push(chars[..i].iter().chain(&chars[i + 1..]).collect());
+        }
+        let mut seen = HashSet::new();
+        smaller.retain(|s| seen.insert(s.clone()));
+        smaller
+    }
+}
+
+impl<T: Shrink> Shrink for Vec<T> {
+    /// Drops each element, then shrinks each element in place.
+    fn shrink(&self) -> Vec<Vec<T>> {
+        let mut smaller = Vec::new();
+        for i in 0..self.len() {
+            let mut v = self.clone();
+            v.remove(i);
+            smaller.push(v);
+        }
+        for (i, item) in self.iter().enumerate() {
+            for s in item.shrink() {
+                let mut v = self.clone();
+                v[i] = s;
+                smaller.push(v);
+            }
+        }
+        smaller
+    }
+}
+
+impl Shrink for u32 {
+    fn shrink(&self) -> Vec<u32> {
+        let mut smaller = Vec::new();
+        if *self > 0 {
+            smaller.push(0);
+            smaller.push(self / 2);
+            smaller.push(self - 1);
+        }
+        smaller.dedup();
+        smaller.retain(|s| s < self);
+        smaller
+    }
+}
+
+/// Repeatedly replaces `input` with its first smaller candidate that still
+/// `fails`, until none does.
+fn minimize<I: Shrink>(mut input: I, fails: impl Fn(&I) -> bool) -> I {
+    'outer: loop {
+        for candidate in input.shrink() {
+            if fails(&candidate) {
+                input = candidate;
+                continue 'outer;
+            }
+        }
+
+        return input;
+    }
+}
+
+/// An input on which the registered implementations gave different outputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement<I, O> {
+    pub input: I,
+    pub outputs: Vec<(&'static str, O)>,
+}
+
+impl<I: fmt::Debug, O: fmt::Debug> fmt::Display for Disagreement<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "implementations disagree on {:?}", self.input)?;
+        for (name, output) in &self.outputs {
+            writeln!(f, "  {}: {:?}", name, output)?;
+        }
+
+        Ok(())
+    }
+}
+
+type Implementation<'a, I, O> = Box<dyn Fn(&I) -> O + 'a>;
+type Assumption<'a, I> = Box<dyn Fn(&I) -> bool + 'a>;
+
+/// Named implementations of the same function, checked against each other on
+/// random inputs. The first registered one is the reference.
+pub struct Differential<'a, I, O> {
+    implementations: Vec<(&'static str, Implementation<'a, I, O>)>,
+    assumptions: Vec<Assumption<'a, I>>,
+}
+
+impl<'a, I, O> Differential<'a, I, O>
+where
+    I: Shrink + fmt::Debug,
+    O: PartialEq + fmt::Debug,
+{
+    pub fn new() -> Differential<'a, I, O> {
+        Differential {
+            implementations: Vec::new(),
+            assumptions: Vec::new(),
+        }
+    }
+
+    pub fn register(mut self, name: &'static str, f: impl Fn(&I) -> O + 'a) -> Self {
+        self.implementations.push((name, Box::new(f)));
+        self
+    }
+
+    /// Restricts shrinking to inputs that are still valid, such as grids that
+    /// stay rectangular.
+    pub fn assume(mut self, valid: impl Fn(&I) -> bool + 'a) -> Self {
+        self.assumptions.push(Box::new(valid));
+        self
+    }
+
+    fn is_valid(&self, input: &I) -> bool {
+        self.assumptions.iter().all(|valid| valid(input))
+    }
+
+    /// Every output if any implementation disagrees with the reference on
+    /// `input`.
+    pub fn check(&self, input: &I) -> Option<Disagreement<I, O>> {
+        let outputs = self
+            .implementations
+            .iter()
+            .map(|(name, f)| (*name, f(input)))
+            .collect::<Vec<(&'static str, O)>>();
+        if outputs.iter().all(|(_, o)| *o == outputs[0].1) {
+            return None;
+        }
+
+        Some(Disagreement {
+            input: input.clone(),
+            outputs,
+        })
+    }
+
+    /// Repeatedly replaces the failing input with the first smaller valid
+    /// input that still fails, until none does.
+    pub fn shrink(&self, failure: Disagreement<I, O>) -> Disagreement<I, O> {
+        let input = minimize(failure.input.clone(), |candidate| {
+            self.is_valid(candidate) && self.check(candidate).is_some()
+        });
+        self.check(&input).unwrap_or(failure)
+    }
+
+    /// Checks `cases` inputs from `generate`, returning the first
+    /// disagreement shrunk to a minimal input.
+    pub fn run(
+        &self,
+        seed: u64,
+        cases: usize,
+        mut generate: impl FnMut(&mut Rng) -> I,
+    ) -> Result<(), Disagreement<I, O>> {
+        let mut rng = Rng::new(seed);
+        for _ in 0..cases {
+            let input = generate(&mut rng);
+            if let Some(failure) = self.check(&input) {
+                return Err(self.shrink(failure));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like [`Differential::run`], but panics with the minimal disagreement.
+    pub fn assert_agree(&self, seed: u64, cases: usize, generate: impl FnMut(&mut Rng) -> I) {
+        if let Err(failure) = self.run(seed, cases, generate) {
+            panic!("{}", failure);
+        }
+    }
+}
+
+/// Checks that `property` holds for `cases` inputs from `generate`, returning
+/// the failing input shrunk to a minimal one.
+pub fn forall<I: Shrink>(
+    seed: u64,
+    cases: usize,
+    mut generate: impl FnMut(&mut Rng) -> I,
+    property: impl Fn(&I) -> bool,
+) -> Result<(), I> {
+    let mut rng = Rng::new(seed);
+    for _ in 0..cases {
+        let input = generate(&mut rng);
+        if !property(&input) {
+            return Err(minimize(input, |candidate| !property(candidate)));
+        }
+    }
+
+    Ok(())
+}
+
+impl<I, O> Default for Differential<'_, I, O>
+where
+    I: Shrink + fmt::Debug,
+    O: PartialEq + fmt::Debug,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_string(rng: &mut Rng) -> String {
+        (0..rng.range(0..20))
+            .map(|_| *rng.choose(&['a', 'b', 'c']))
+            .collect()
+    }
+
+    #[test]
+    fn test_agreeing_implementations() {
+        Differential::new()
+            .register("filter", |s: &String| {
+                s.chars().filter(|&c| c == 'a').count()
+            })
+            .register("matches", |s: &String| s.matches('a').count())
+            .assert_agree(1, 500, random_string);
+    }
+
+    #[test]
+    fn test_disagreement_is_shrunk() {
+        let failure = Differential::new()
+            .register("count", |s: &String| s.matches('a').count())
+            .register("capped", |s: &String| s.matches('a').count().min(2))
+            .run(2, 500, random_string)
+            .unwrap_err();
+        assert_eq!(failure.input, "aaa");
+        assert_eq!(failure.outputs, vec![("count", 3), ("capped", 2)]);
+        assert!(failure.to_string().contains("capped: 2"));
+    }
+
+    #[test]
+    fn test_assumptions_limit_shrinking() {
+        let failure = Differential::new()
+            .register("sum", |v: &Vec<u32>| v.iter().sum::<u32>())
+            .register("first", |v: &Vec<u32>| {
+                v.first().copied().unwrap_or_default()
+            })
+            .assume(|v: &Vec<u32>| v.len() >= 2)
+            .run(3, 100, |rng| {
+                (0..rng.range(2..6))
+                    .map(|_| rng.range(0..50) as u32)
+                    .collect()
+            })
+            .unwrap_err();
+        assert_eq!(failure.input, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_forall() {
+        assert_eq!(forall(4, 200, random_string, |s| s.len() < 30), Ok(()));
+        assert_eq!(
+            forall(4, 200, random_string, |s| !s.contains("ba")),
+            Err("ba".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shrink_candidates() {
+        assert_eq!("abc".to_string().shrink(), vec!["bc", "a", "ac", "ab"]);
+        assert_eq!("aa".to_string().shrink(), vec!["a"]);
+        assert_eq!(4u32.shrink(), vec![0, 2, 3]);
+        assert_eq!(1u32.shrink(), vec![0]);
+        assert!(0u32.shrink().is_empty());
+        assert_eq!(
+            vec![1u32, 2].shrink(),
+            vec![vec![2], vec![1], vec![0, 2], vec![1, 0], vec![1, 1]]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{
+    answer::{Answer, AnswerError},
+    random::Rng,
+    reference::{self, COLOURS, WORDS},
+};
+
+/// A generated puzzle input together with the answers a correct solution
+/// prints for it, labelled the way the day's `main` labels them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub answers: Vec<(&'static str, u64)>,
+}
+
+/// Words sharing letters, where only one of the two can be matched if a
+/// solution consumes the line as it scans it.
+const OVERLAPS: [&str; 8] = [
+    "twone",
+    "oneight",
+    "eightwo",
+    "eighthree",
+    "threeight",
+    "fiveight",
+    "sevenine",
+    "nineight",
+];
+
+#[derive(Debug, Clone)]
+pub struct Day1Config {
+    pub lines: usize,
+    /// Most pieces (digits, words, overlaps or filler) per line.
+    pub max_pieces: usize,
+    /// Chance in percent of a piece being one of the overlapping words.
+    pub overlap_percent: u64,
+}
+
+impl Default for Day1Config {
+    fn default() -> Day1Config {
+        Day1Config {
+            lines: 1000,
+            max_pieces: 8,
+            overlap_percent: 20,
+        }
+    }
+}
+
+/// Calibration lines, each with at least one digit so both parts have an
+/// answer.
+pub fn day1(seed: u64, config: &Day1Config) -> Generated {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    let (mut digits, mut words) = (0, 0);
+    for _ in 0..config.lines {
+        let mut pieces = Vec::new();
+        for _ in 0..rng.range(1..config.max_pieces.max(1) + 1) {
+            let piece = if rng.chance(config.overlap_percent) {
+                rng.choose(&OVERLAPS).to_string()
+            } else {
+                match rng.below(3) {
+                    0 => char::from(b'1' + rng.below(9) as u8).to_string(),
+                    1 => rng.choose(&WORDS).to_string(),
+                    _ => (0..rng.range(1..4))
+                        .map(|_| char::from(b'a' + rng.below(26) as u8))
+                        .collect(),
+                }
+            };
+            pieces.push(piece);
+        }
+        if !pieces.iter().any(|p| p.bytes().any(|b| b.is_ascii_digit())) {
+            let at = rng.range(0..pieces.len() + 1);
+            pieces.insert(at, char::from(b'1' + rng.below(9) as u8).to_string());
+        }
+
+        let line = pieces.concat();
+        digits += reference::calibration(&line, false);
+        words += reference::calibration(&line, true);
+        input.push_str(&line);
+        input.push('\n');
+    }
+
+    Generated {
+        input,
+        answers: vec![("digits", digits), ("acc", words)],
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Day2Config {
+    pub games: usize,
+    pub max_sets: usize,
+    /// Most cubes of a single colour revealed at once.
+    pub max_cubes: u32,
+}
+
+impl Default for Day2Config {
+    fn default() -> Day2Config {
+        Day2Config {
+            games: 100,
+            max_sets: 6,
+            max_cubes: 20,
+        }
+    }
+}
+
+/// Game records, each set revealing one to three colours in random order.
+/// Fails if an answer overflows, which large `max_cubes` can cause.
+pub fn day2(seed: u64, config: &Day2Config) -> Result<Generated, AnswerError> {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for id in 1..=config.games {
+        let sets = (0..rng.range(1..config.max_sets.max(1) + 1))
+            .map(|_| {
+                let mut order = [0, 1, 2];
+                for i in (1..order.len()).rev() {
+                    order.swap(i, rng.range(0..i + 1));
+                }
+                order[..rng.range(1..4)]
+                    .iter()
+                    .map(|&c| {
+                        let count = rng.range(1..config.max_cubes.max(1) as usize + 1);
+                        format!("{} {}", count, COLOURS[c])
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .collect::<Vec<String>>();
+        input.push_str(&format!("Game {}: {}\n", id, sets.join("; ")));
+    }
+
+    let (ids, power) = reference::games(&input)?;
+    Ok(Generated {
+        input,
+        answers: vec![("sum of ids", ids), ("smallest bag power sum", power)],
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Day3Config {
+    pub width: usize,
+    pub height: usize,
+    /// Chance in percent of a number starting at a free cell.
+    pub number_percent: u64,
+    /// Chance in percent of a symbol at a cell that does not start a number.
+    pub symbol_percent: u64,
+    pub symbols: Vec<char>,
+    pub max_digits: usize,
+}
+
+impl Default for Day3Config {
+    fn default() -> Day3Config {
+        Day3Config {
+            width: 140,
+            height: 140,
+            number_percent: 10,
+            symbol_percent: 5,
+            symbols: "*#+$/@=%&-".chars().collect(),
+            max_digits: 3,
+        }
+    }
+}
+
+/// An engine schematic `width` characters wide. Numbers never touch
+/// horizontally and have no leading zeros. Fails if a part number or the sum
+/// overflows, which large `max_digits` can cause.
+pub fn day3(seed: u64, config: &Day3Config) -> Result<Generated, AnswerError> {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..config.height {
+        let mut row = Vec::with_capacity(config.width);
+        while row.len() < config.width {
+            let last_digit = row.last().is_some_and(|c: &char| c.is_ascii_digit());
+            if !last_digit && rng.chance(config.number_percent) {
+                let len = rng.range(1..config.max_digits.max(1) + 1);
+                for i in 0..len.min(config.width - row.len()) {
+                    let low = if i == 0 { 1 } else { 0 };
+                    row.push(char::from(b'0' + rng.range(low..10) as u8));
+                }
+            } else if !config.symbols.is_empty() && rng.chance(config.symbol_percent) {
+                row.push(*rng.choose(&config.symbols));
+            } else {
+                row.push('.');
+            }
+        }
+        input.extend(row);
+        input.push('\n');
+    }
+
+    let sum = Answer::sum(reference::part_numbers(&input)?, "sum")?;
+    Ok(Generated {
+        input,
+        answers: vec![("sum", sum.value())],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generators_are_seeded() {
+        let config = Day1Config::default();
+        assert_eq!(day1(7, &config), day1(7, &config));
+        assert_ne!(day1(7, &config), day1(8, &config));
+        assert_eq!(
+            day2(7, &Day2Config::default()).unwrap(),
+            day2(7, &Day2Config::default()).unwrap()
+        );
+        assert_eq!(
+            day3(7, &Day3Config::default()).unwrap(),
+            day3(7, &Day3Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_day1_lines_have_digits() {
+        let config = Day1Config {
+            lines: 200,
+            max_pieces: 3,
+            overlap_percent: 50,
+        };
+        let generated = day1(1, &config);
+        assert_eq!(generated.input.lines().count(), 200);
+        assert!(generated
+            .input
+            .lines()
+            .all(|l| l.bytes().any(|b| b.is_ascii_digit())));
+        assert!(OVERLAPS.iter().any(|o| generated.input.contains(o)));
+    }
+
+    #[test]
+    fn test_day2_respects_max_cubes() {
+        let config = Day2Config {
+            games: 50,
+            max_sets: 4,
+            max_cubes: 5,
+        };
+        let generated = day2(2, &config).unwrap();
+        assert_eq!(generated.input.lines().count(), 50);
+        for record in generated.input.lines() {
+            assert!(reference::fewest_cubes(record).iter().all(|&c| c <= 5));
+        }
+        // Every game fits in the bag, so the ids sum to 1 + 2 + ... + 50.
+        assert_eq!(generated.answers[0], ("sum of ids", 1275));
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        let config = Day2Config {
+            games: 20,
+            max_sets: 3,
+            max_cubes: u32::MAX,
+        };
+        assert_eq!(
+            day2(1, &config),
+            Err(AnswerError::Overflow("smallest bag power".to_string()))
+        );
+
+        let config = Day3Config {
+            width: 60,
+            height: 4,
+            number_percent: 100,
+            symbol_percent: 100,
+            max_digits: 30,
+            ..Day3Config::default()
+        };
+        assert_eq!(
+            day3(1, &config),
+            Err(AnswerError::Overflow("part number".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_day3_width_counts_chars() {
+        let config = Day3Config {
+            width: 5,
+            height: 3,
+            symbol_percent: 100,
+            symbols: vec!['é'],
+            ..Day3Config::default()
+        };
+        let generated = day3(4, &config).unwrap();
+        assert!(generated.input.lines().all(|l| l.chars().count() == 5));
+    }
+
+    #[test]
+    fn test_day3_shape() {
+        let config = Day3Config {
+            width: 30,
+            height: 12,
+            number_percent: 40,
+            symbol_percent: 30,
+            symbols: vec!['*'],
+            max_digits: 4,
+        };
+        let generated = day3(3, &config).unwrap();
+        assert_eq!(generated.input.lines().count(), 12);
+        assert!(generated.input.lines().all(|l| l.len() == 30));
+        assert!(generated
+            .input
+            .chars()
+            .all(|c| c.is_ascii_digit() || ".*\n".contains(c)));
+    }
+}
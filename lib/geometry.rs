@@ -0,0 +1,178 @@
+use std::ops::Add;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub fn new(x: i64, y: i64, z: i64) -> Point3 {
+        Point3 { x, y, z }
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+/// Axis-aligned box of integer cells, including both corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Box3 {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Box3 {
+    /// Box spanning the two corners, given in any order.
+    pub fn new(a: Point3, b: Point3) -> Box3 {
+        Box3 {
+            min: Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    pub fn translate(&self, offset: Point3) -> Box3 {
+        Box3 {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    pub fn volume(&self) -> i64 {
+        (self.max.x - self.min.x + 1)
+            * (self.max.y - self.min.y + 1)
+            * (self.max.z - self.min.z + 1)
+    }
+
+    pub fn overlaps_xy(&self, other: &Box3) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn intersects(&self, other: &Box3) -> bool {
+        self.overlaps_xy(other) && self.min.z <= other.max.z && other.min.z <= self.max.z
+    }
+
+    /// The `(x, y)` cells covered when looking down the z axis.
+    pub fn footprint(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        (self.min.y..=self.max.y).flat_map(move |y| (self.min.x..=self.max.x).map(move |x| (x, y)))
+    }
+}
+
+/// Twice the area enclosed by a simple polygon, using the shoelace formula.
+/// Vertices are given in order, either clockwise or counter-clockwise, and the
+/// polygon is closed implicitly. `None` if the area overflows.
+pub fn shoelace_double_area(vertices: &[(i64, i64)]) -> Option<i64> {
+    let n = vertices.len();
+    let sum = (0..n).try_fold(0i64, |sum, i| {
+        let (x0, y0) = vertices[i];
+        let (x1, y1) = vertices[(i + 1) % n];
+        sum.checked_add(x0.checked_mul(y1)?.checked_sub(x1.checked_mul(y0)?)?)
+    })?;
+
+    sum.checked_abs()
+}
+
+/// Number of lattice points strictly inside a lattice polygon, from Pick's
+/// theorem `A = i + b/2 - 1`. `None` if it overflows.
+pub fn pick_interior(double_area: i64, boundary: i64) -> Option<i64> {
+    Some(double_area.checked_sub(boundary)?.checked_add(2)? / 2)
+}
+
+/// Number of lattice points on the boundary of a polygon whose edges are all
+/// horizontal or vertical. `None` if it overflows.
+pub fn rectilinear_boundary(vertices: &[(i64, i64)]) -> Option<i64> {
+    let n = vertices.len();
+    (0..n).try_fold(0i64, |sum, i| {
+        let (x0, y0) = vertices[i];
+        let (x1, y1) = vertices[(i + 1) % n];
+        let dx = x1.checked_sub(x0)?.checked_abs()?;
+        let dy = y1.checked_sub(y0)?.checked_abs()?;
+        sum.checked_add(dx.checked_add(dy)?)
+    })
+}
+
+/// Lattice points inside or on the boundary of a rectilinear polygon, or
+/// `None` if the count overflows.
+pub fn rectilinear_filled_area(vertices: &[(i64, i64)]) -> Option<i64> {
+    let boundary = rectilinear_boundary(vertices)?;
+    pick_interior(shoelace_double_area(vertices)?, boundary)?.checked_add(boundary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box3() {
+        let a = Box3::new(Point3::new(1, 0, 1), Point3::new(1, 2, 1));
+        assert_eq!(a.min, Point3::new(1, 0, 1));
+        assert_eq!(a.volume(), 3);
+        assert_eq!(
+            a.footprint().collect::<Vec<(i64, i64)>>(),
+            vec![(1, 0), (1, 1), (1, 2)]
+        );
+
+        let b = Box3::new(Point3::new(2, 2, 2), Point3::new(0, 2, 2));
+        assert_eq!(b.min, Point3::new(0, 2, 2));
+        assert!(a.overlaps_xy(&b));
+        assert!(!a.intersects(&b));
+        assert!(a.translate(Point3::new(0, 0, 1)).intersects(&b));
+
+        let c = Box3::new(Point3::new(2, 0, 5), Point3::new(2, 1, 5));
+        assert!(!a.overlaps_xy(&c));
+    }
+
+    #[test]
+    fn test_shoelace_square() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(shoelace_double_area(&square), Some(32));
+
+        let reversed = square.iter().rev().copied().collect::<Vec<(i64, i64)>>();
+        assert_eq!(shoelace_double_area(&reversed), Some(32));
+    }
+
+    #[test]
+    fn test_shoelace_triangle() {
+        assert_eq!(shoelace_double_area(&[(0, 0), (3, 0), (0, 3)]), Some(9));
+    }
+
+    #[test]
+    fn test_pick_interior() {
+        // 4x4 square has 16 boundary points and 3x3 inside
+        assert_eq!(pick_interior(32, 16), Some(9));
+    }
+
+    #[test]
+    fn test_rectilinear() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(rectilinear_boundary(&square), Some(16));
+        assert_eq!(rectilinear_filled_area(&square), Some(25));
+
+        // L shape covering a 3x3 block minus its top right 2x2 corner
+        let l_shape = [(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (0, 2)];
+        assert_eq!(rectilinear_boundary(&l_shape), Some(8));
+        assert_eq!(rectilinear_filled_area(&l_shape), Some(8));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = i64::MAX / 2 + 1;
+        let square = [(0, 0), (big, 0), (big, big), (0, big)];
+        assert_eq!(shoelace_double_area(&square), None);
+        assert_eq!(rectilinear_boundary(&square), None);
+        assert_eq!(rectilinear_filled_area(&square), None);
+        assert_eq!(pick_interior(i64::MAX, 0), None);
+
+        let line = [(i64::MIN, 0), (i64::MAX, 0)];
+        assert_eq!(rectilinear_boundary(&line), None);
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::grid::{Direction, Grid};
+
+/// Directed graph stored as adjacency lists, with a value `N` on each node and
+/// a weight `E` on each edge. Undirected graphs store every edge both ways.
+#[derive(Debug, Clone)]
+pub struct Graph<N, E> {
+    nodes: Vec<N>,
+    edges: Vec<Vec<(usize, E)>>,
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn new() -> Graph<N, E> {
+        Graph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: E) {
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn edges(&self, index: usize) -> &[(usize, E)] {
+        &self.edges[index]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(|e| e.len()).sum::<usize>()
+    }
+
+    pub fn predecessors(&self, index: usize) -> Vec<usize> {
+        (0..self.len())
+            .filter(|from| self.edges[*from].iter().any(|(to, _)| *to == index))
+            .collect::<Vec<usize>>()
+    }
+
+    pub fn find<F>(&self, pred: F) -> Option<usize>
+    where
+        F: Fn(&N) -> bool,
+    {
+        self.nodes.iter().position(pred)
+    }
+
+    /// Minimum cut between `source` and `sink`, counting every edge as one
+    /// unit of capacity, found with Edmonds-Karp. Gives up and returns `None`
+    /// once the flow exceeds `limit`. Otherwise returns the size of the cut
+    /// and which nodes are on the source side of it.
+    pub fn min_cut(&self, source: usize, sink: usize, limit: usize) -> Option<(usize, Vec<bool>)> {
+        let mut capacity: HashMap<(usize, usize), i64> = HashMap::new();
+        let mut adjacent = vec![Vec::new(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, _) in edges {
+                *capacity.entry((from, *to)).or_default() += 1;
+                adjacent[from].push(*to);
+                adjacent[*to].push(from);
+            }
+        }
+
+        let mut flow: HashMap<(usize, usize), i64> = HashMap::new();
+        let residual = |flow: &HashMap<(usize, usize), i64>, u: usize, v: usize| {
+            capacity.get(&(u, v)).copied().unwrap_or_default()
+                - flow.get(&(u, v)).copied().unwrap_or_default()
+        };
+
+        let mut total = 0;
+        loop {
+            let mut parent = vec![None; self.len()];
+            let mut reached = vec![false; self.len()];
+            let mut queue = VecDeque::from([source]);
+            reached[source] = true;
+
+            while let Some(u) = queue.pop_front() {
+                for v in &adjacent[u] {
+                    if !reached[*v] && residual(&flow, u, *v) > 0 {
+                        reached[*v] = true;
+                        parent[*v] = Some(u);
+                        queue.push_back(*v);
+                    }
+                }
+            }
+
+            if !reached[sink] {
+                return Some((total, reached));
+            }
+
+            total += 1;
+            if total > limit {
+                return None;
+            }
+
+            let mut v = sink;
+            while let Some(u) = parent[v] {
+                *flow.entry((u, v)).or_default() += 1;
+                *flow.entry((v, u)).or_default() -= 1;
+                v = u;
+            }
+        }
+    }
+
+    /// Graphviz DOT source for the graph, with each node labelled by
+    /// `node_label`. Undirected graphs draw each edge pair once.
+    pub fn to_dot<F>(&self, name: &str, directed: bool, node_label: F) -> String
+    where
+        F: Fn(&N) -> String,
+    {
+        let (kind, arrow) = if directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let mut out = format!("{} {} {{\n", kind, name);
+        for (i, node) in self.nodes.iter().enumerate() {
+            let label = node_label(node).replace('"', "\\\"");
+            out.push_str(&format!("    n{} [label=\"{}\"];\n", i, label));
+        }
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, _) in edges {
+                if directed || from <= *to {
+                    out.push_str(&format!("    n{} {} n{};\n", from, arrow, to));
+                }
+            }
+        }
+        out.push_str("}\n");
+
+        out
+    }
+}
+
+impl<N, E: Clone> Graph<N, E> {
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: E) {
+        self.edges[a].push((b, weight.clone()));
+        self.edges[b].push((a, weight));
+    }
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Graph::new()
+    }
+}
+
+/// Collapses the corridors of a grid into a graph whose nodes are the
+/// junctions (open cells with three or more open neighbours) plus `extra`
+/// cells such as the start and goal. Each edge is weighted by the number of
+/// steps along the corridor, walked with `moves` so one-way cells only give
+/// edges in the direction they allow.
+pub fn compress_grid<T, O, M>(
+    grid: &Grid<T>,
+    extra: &[(usize, usize)],
+    is_open: O,
+    moves: M,
+) -> Graph<(usize, usize), usize>
+where
+    O: Fn(&T) -> bool,
+    M: Fn((usize, usize)) -> Vec<(usize, usize)>,
+{
+    let mut graph = Graph::new();
+    for (x, y) in grid.positions() {
+        if !is_open(grid.get(x, y)) {
+            continue;
+        }
+
+        let open = Direction::ALL
+            .iter()
+            .filter_map(|d| grid.step(x, y, *d))
+            .filter(|(nx, ny)| is_open(grid.get(*nx, *ny)))
+            .count();
+        if open >= 3 || extra.contains(&(x, y)) {
+            graph.add_node((x, y));
+        }
+    }
+
+    for from in 0..graph.len() {
+        for first in moves(*graph.node(from)) {
+            let mut prev = *graph.node(from);
+            let mut pos = first;
+            let mut steps = 1;
+
+            loop {
+                if let Some(to) = graph.find(|n| *n == pos) {
+                    if to != from {
+                        graph.add_edge(from, to, steps);
+                    }
+                    break;
+                }
+
+                let next = moves(pos)
+                    .into_iter()
+                    .filter(|p| *p != prev)
+                    .collect::<Vec<(usize, usize)>>();
+                let [next] = next[..] else {
+                    break;
+                };
+
+                prev = pos;
+                pos = next;
+                steps += 1;
+            }
+        }
+    }
+
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> Graph<&'static str, u32> {
+        let mut graph = Graph::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        graph.add_edge(a, b, 1);
+        graph.add_edge(b, c, 2);
+        graph.add_edge(a, c, 3);
+        graph
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = triangle();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.edges(0), &[(1, 1), (2, 3)]);
+        assert_eq!(graph.predecessors(2), vec![0, 1]);
+        assert_eq!(graph.predecessors(0), Vec::<usize>::new());
+        assert_eq!(graph.find(|n| *n == "c"), Some(2));
+        assert_eq!(*graph.node(1), "b");
+    }
+
+    #[test]
+    fn test_undirected() {
+        let mut graph: Graph<(), ()> = Graph::new();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_undirected_edge(a, b, ());
+        assert_eq!(graph.edges(a), &[(b, ())]);
+        assert_eq!(graph.edges(b), &[(a, ())]);
+    }
+
+    #[test]
+    fn test_compress_grid() {
+        // two junctions joined by an upper and a lower corridor
+        let grid = Grid::from_lines(&["#S#####", "#.....#", "#.###.#", "#.....#", "#####G#"]);
+        let open = |(x, y): (usize, usize)| {
+            Direction::ALL
+                .iter()
+                .filter_map(|d| grid.step(x, y, *d))
+                .filter(|(nx, ny)| *grid.get(*nx, *ny) != '#')
+                .collect::<Vec<(usize, usize)>>()
+        };
+
+        let graph = compress_grid(&grid, &[(1, 0), (5, 4)], |c| *c != '#', open);
+        assert_eq!(graph.nodes(), &[(1, 0), (1, 1), (5, 3), (5, 4)]);
+
+        let mut edges = (0..graph.len())
+            .flat_map(|from| graph.edges(from).iter().map(move |(to, w)| (from, *to, *w)))
+            .collect::<Vec<(usize, usize, usize)>>();
+        edges.sort_unstable();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 1, 1),
+                (1, 0, 1),
+                (1, 2, 6),
+                (1, 2, 6),
+                (2, 1, 6),
+                (2, 1, 6),
+                (2, 3, 1),
+                (3, 2, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_cut() {
+        // two triangles joined by a single bridge
+        let mut graph: Graph<(), ()> = Graph::new();
+        for _ in 0..6 {
+            graph.add_node(());
+        }
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.add_undirected_edge(a, b, ());
+        }
+
+        let (cut, side) = graph.min_cut(0, 5, 3).unwrap();
+        assert_eq!(cut, 1);
+        assert_eq!(side, vec![true, true, true, false, false, false]);
+
+        let (cut, _) = graph.min_cut(0, 1, 3).unwrap();
+        assert_eq!(cut, 2);
+        assert_eq!(graph.min_cut(0, 1, 1), None);
+    }
+
+    #[test]
+    fn test_min_cut_directed() {
+        let graph = triangle();
+        assert_eq!(graph.min_cut(0, 2, 5), Some((2, vec![true, false, false])));
+        assert_eq!(graph.min_cut(2, 0, 5), Some((0, vec![false, false, true])));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        assert_eq!(
+            triangle().to_dot("t", true, |n| n.to_string()),
+            "digraph t {
+    n0 [label=\"a\"];
+    n1 [label=\"b\"];
+    n2 [label=\"c\"];
+    n0 -> n1;
+    n0 -> n2;
+    n1 -> n2;
+}
+"
+        );
+
+        let mut graph: Graph<&str, ()> = Graph::new();
+        let a = graph.add_node("a\"");
+        let b = graph.add_node("b");
+        graph.add_undirected_edge(a, b, ());
+        assert_eq!(
+            graph.to_dot("u", false, |n| n.to_string()),
+            "graph u {
+    n0 [label=\"a\\\"\"];
+    n1 [label=\"b\"];
+    n0 -- n1;
+}
+"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn delta(&self) -> (i64, i64) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        self.turn_left().opposite()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: Vec<Vec<T>>,
+}
+
+impl<T> Grid<T> {
+    pub fn new() -> Grid<T> {
+        Grid { rows: Vec::new() }
+    }
+
+    pub fn push_row(&mut self, row: Vec<T>) {
+        self.rows.push(row);
+    }
+
+    pub fn verify(&self) -> bool {
+        let first_len = self.width();
+        self.rows.iter().all(|row| row.len() == first_len)
+    }
+
+    /// [`Grid::verify`] as an `InvalidData` error, for loading input.
+    pub fn ensure_rectangular(&self) -> io::Result<()> {
+        if !self.verify() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "grid rows differ in length",
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map(|r| r.len()).unwrap_or_default()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.rows[y]
+    }
+
+    pub fn rows(&self) -> &[Vec<T>] {
+        &self.rows
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        &self.rows[y][x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self.rows[y][x] = value;
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        y >= 0
+            && (y as usize) < self.rows.len()
+            && x >= 0
+            && (x as usize) < self.rows[y as usize].len()
+    }
+
+    /// Position `add_x`, `add_y` away from `x`, `y`, or `None` if it falls
+    /// outside the grid.
+    pub fn offset(&self, x: usize, y: usize, add_x: i64, add_y: i64) -> Option<(usize, usize)> {
+        let new_x = x as i64 + add_x;
+        let new_y = y as i64 + add_y;
+
+        if !self.contains(new_x, new_y) {
+            return None;
+        }
+
+        Some((new_x as usize, new_y as usize))
+    }
+
+    pub fn get_offset(&self, x: usize, y: usize, add_x: i64, add_y: i64) -> Option<&T> {
+        self.offset(x, y, add_x, add_y).map(|(x, y)| self.get(x, y))
+    }
+
+    /// Cell at `x`, `y` when the grid is tiled infinitely in every direction.
+    pub fn get_wrapped(&self, x: i64, y: i64) -> &T {
+        let y = y.rem_euclid(self.height() as i64) as usize;
+        let x = x.rem_euclid(self.rows[y].len() as i64) as usize;
+        self.get(x, y)
+    }
+
+    pub fn step(&self, x: usize, y: usize, dir: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = dir.delta();
+        self.offset(x, y, dx, dy)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| (x, y)))
+    }
+
+    pub fn find<F>(&self, pred: F) -> Option<(usize, usize)>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.positions().find(|&(x, y)| pred(self.get(x, y)))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Grid {
+            rows: self
+                .rows
+                .iter()
+                .map(|row| row.iter().map(&f).collect::<Vec<U>>())
+                .collect::<Vec<Vec<U>>>(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            rows: vec![vec![value; width]; height],
+        }
+    }
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+impl Grid<char> {
+    pub fn add_row(&mut self, row: &str) {
+        self.rows.push(row.chars().collect::<Vec<char>>());
+    }
+
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Grid<char> {
+        let mut grid = Grid::new();
+        for line in lines {
+            grid.add_row(line.as_ref());
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::North.opposite(), Direction::South);
+        assert_eq!(Direction::East.turn_left(), Direction::North);
+        assert_eq!(Direction::East.turn_right(), Direction::South);
+        for dir in Direction::ALL {
+            let (dx, dy) = dir.delta();
+            let (ox, oy) = dir.opposite().delta();
+            assert_eq!((dx + ox, dy + oy), (0, 0));
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut grid = Grid::from_lines(&["abc", "def"]);
+        assert!(grid.verify());
+        grid.add_row("gh");
+        assert!(!grid.verify());
+        assert_eq!(
+            grid.ensure_rectangular().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(Grid::<char>::new().verify());
+        assert!(Grid::from_lines(&["ab", "cd"]).ensure_rectangular().is_ok());
+    }
+
+    #[test]
+    fn test_get_offset() {
+        let grid = Grid::from_lines(&["abc", "def"]);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(1, 1), &'e');
+        assert_eq!(grid.get_offset(0, 0, 1, 1), Some(&'e'));
+        assert_eq!(grid.get_offset(0, 0, -1, 0), None);
+        assert_eq!(grid.get_offset(2, 1, 1, 0), None);
+        assert_eq!(grid.get_offset(2, 1, 0, 1), None);
+    }
+
+    #[test]
+    fn test_get_wrapped() {
+        let grid = Grid::from_lines(&["abc", "def"]);
+        assert_eq!(grid.get_wrapped(1, 1), &'e');
+        assert_eq!(grid.get_wrapped(4, 3), &'e');
+        assert_eq!(grid.get_wrapped(-1, -1), &'f');
+        assert_eq!(grid.get_wrapped(-6, -4), &'a');
+    }
+
+    #[test]
+    fn test_step() {
+        let grid = Grid::from_lines(&["abc", "def"]);
+        assert_eq!(grid.step(1, 0, Direction::South), Some((1, 1)));
+        assert_eq!(grid.step(1, 0, Direction::North), None);
+        assert_eq!(grid.step(2, 1, Direction::West), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_find_and_map() {
+        let grid = Grid::from_lines(&["..", ".S"]);
+        assert_eq!(grid.find(|c| *c == 'S'), Some((1, 1)));
+        assert_eq!(grid.find(|c| *c == 'x'), None);
+
+        let mask = grid.map(|c| *c == 'S');
+        assert_eq!(mask.row(1), &[false, true]);
+        assert_eq!(grid.positions().count(), 4);
+    }
+}
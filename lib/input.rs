@@ -0,0 +1,129 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Lines},
+    path::Path,
+};
+
+pub fn lines<P: AsRef<Path>>(path: P) -> io::Result<Lines<BufReader<File>>> {
+    let file = File::open(path)?;
+    Ok(BufReader::new(file).lines())
+}
+
+/// Groups of consecutive non-blank lines, split on one or more blank lines.
+pub fn read_blocks<R: BufRead>(reader: R) -> io::Result<Vec<Vec<String>>> {
+    let mut blocks = Vec::new();
+    let mut block = Vec::new();
+    for l in reader.lines() {
+        let line = l?;
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(block);
+                block = Vec::new();
+            }
+        } else {
+            block.push(line);
+        }
+    }
+
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    Ok(blocks)
+}
+
+/// Streams the fields between `delimiter` bytes, trimmed of surrounding
+/// whitespace, without first reading whole lines into memory. Empty fields
+/// are skipped.
+pub fn read_fields<R: BufRead>(
+    reader: R,
+    delimiter: u8,
+) -> impl Iterator<Item = io::Result<String>> {
+    reader
+        .split(delimiter)
+        .map(|field| {
+            // Trimmed in place, so the field is not copied a second time.
+            let mut field = field?;
+            let start = field.len() - field.trim_ascii_start().len();
+            let end = field.trim_ascii_end().len();
+            field.truncate(end);
+            field.drain(..start.min(end));
+            String::from_utf8(field).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .filter(|field| !matches!(field, Ok(f) if f.is_empty()))
+}
+
+pub fn fields<P: AsRef<Path>>(
+    path: P,
+    delimiter: u8,
+) -> io::Result<impl Iterator<Item = io::Result<String>>> {
+    let file = File::open(path)?;
+    Ok(read_fields(BufReader::new(file), delimiter))
+}
+
+pub fn blocks<P: AsRef<Path>>(path: P) -> io::Result<Vec<Vec<String>>> {
+    let file = File::open(path)?;
+    read_blocks(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_missing_file() {
+        assert!(lines("./does/not/exist").is_err());
+    }
+
+    #[test]
+    fn test_lines() {
+        let lines = lines("./bin/day2/input").unwrap();
+        assert_eq!(lines.count(), 100);
+    }
+
+    #[test]
+    fn test_read_fields() {
+        let fields = read_fields("rn=1,cm-,,qp=3\n".as_bytes(), b',')
+            .collect::<io::Result<Vec<String>>>()
+            .unwrap();
+        assert_eq!(fields, vec!["rn=1", "cm-", "qp=3"]);
+    }
+
+    #[test]
+    fn test_read_fields_trims_both_ends() {
+        let fields = read_fields(" \tab ,  , c,\nd\r\n".as_bytes(), b',')
+            .collect::<io::Result<Vec<String>>>()
+            .unwrap();
+        assert_eq!(fields, vec!["ab", "c", "d"]);
+    }
+
+    #[test]
+    fn test_read_fields_invalid_utf8() {
+        let mut fields = read_fields(&b"ab,\xff,cd"[..], b',');
+        assert_eq!(fields.next().unwrap().unwrap(), "ab");
+        assert!(fields.next().unwrap().is_err());
+        assert_eq!(fields.next().unwrap().unwrap(), "cd");
+    }
+
+    #[test]
+    fn test_read_blocks() {
+        let blocks = read_blocks("ab\ncd\n\nef\n\n\ngh\nij\n".as_bytes()).unwrap();
+        assert_eq!(
+            blocks,
+            vec![
+                vec!["ab".to_string(), "cd".to_string()],
+                vec!["ef".to_string()],
+                vec!["gh".to_string(), "ij".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_blocks_edges() {
+        assert!(read_blocks("".as_bytes()).unwrap().is_empty());
+        assert_eq!(
+            read_blocks("\n\nab\r\n  \ncd".as_bytes()).unwrap(),
+            vec![vec!["ab".to_string()], vec!["cd".to_string()]]
+        );
+    }
+}
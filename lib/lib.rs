@@ -0,0 +1,13 @@
+pub mod answer;
+pub mod cycle;
+pub mod differential;
+pub mod generate;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod math;
+pub mod nonogram;
+pub mod pathfinding;
+pub mod random;
+pub mod reference;
@@ -0,0 +1,568 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// Successive differences of a sequence, down to the first row that is all
+/// zeros. The first row is the sequence itself.
+#[derive(Debug, PartialEq)]
+pub struct DifferenceTable {
+    rows: Vec<Vec<i64>>,
+}
+
+impl DifferenceTable {
+    /// Returns `None` for an empty sequence or if a difference overflows.
+    pub fn new(values: &[i64]) -> Option<DifferenceTable> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut rows = vec![values.to_vec()];
+        while rows.last()?.iter().any(|v| *v != 0) {
+            let row = rows
+                .last()?
+                .windows(2)
+                .map(|w| w[1].checked_sub(w[0]))
+                .collect::<Option<Vec<i64>>>()?;
+            rows.push(row);
+        }
+
+        Some(DifferenceTable { rows })
+    }
+
+    pub fn rows(&self) -> &[Vec<i64>] {
+        &self.rows
+    }
+
+    pub fn extrapolate_forward(&self) -> Option<i64> {
+        self.rows
+            .iter()
+            .rev()
+            .try_fold(0i64, |acc, row| match row.last() {
+                Some(v) => v.checked_add(acc),
+                None => Some(acc),
+            })
+    }
+
+    /// Value `steps` places past the end of the sequence, from Newton's
+    /// forward difference formula. Exact when the last row is all zeros.
+    /// `None` only if the value does not fit in an `i64`, or a binomial
+    /// coefficient it needs does not fit in an `i128`.
+    pub fn extrapolate_forward_by(&self, steps: u64) -> Option<i64> {
+        let n = (self.rows[0].len() - 1) as i128 + i128::from(steps);
+
+        // Terms from an all-zero row on are zero, so C(n, k) is only built as
+        // far as it is needed. C(n, k) = C(n, k - 1) * (n - k + 1) / k is exact.
+        let mut value: i128 = 0;
+        let mut binomial: i128 = 1;
+        for (k, row) in self.rows.iter().enumerate() {
+            if row.iter().all(|v| *v == 0) {
+                break;
+            }
+
+            if k > 0 {
+                binomial = binomial.checked_mul(n - (k as i128 - 1))? / k as i128;
+            }
+            value = value.checked_add(binomial.checked_mul(i128::from(row[0]))?)?;
+        }
+
+        i64::try_from(value).ok()
+    }
+
+    pub fn extrapolate_backward(&self) -> Option<i64> {
+        self.rows
+            .iter()
+            .rev()
+            .try_fold(0i64, |acc, row| match row.first() {
+                Some(v) => v.checked_sub(acc),
+                None => Some(acc),
+            })
+    }
+}
+
+impl fmt::Display for DifferenceTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (depth, row) in self.rows.iter().enumerate() {
+            let values = row.iter().map(|v| v.to_string()).collect::<Vec<String>>();
+            writeln!(f, "{}{}", " ".repeat(depth * 2), values.join("   "))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn extrapolate_forward(values: &[i64]) -> Option<i64> {
+    DifferenceTable::new(values)?.extrapolate_forward()
+}
+
+pub fn extrapolate_backward(values: &[i64]) -> Option<i64> {
+    DifferenceTable::new(values)?.extrapolate_backward()
+}
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Least common multiple, or `None` if it does not fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Greatest common divisor of the magnitudes, or `None` if it is `2^127`,
+/// which needs both to be zero or `i128::MIN`.
+fn gcd_i128(a: i128, b: i128) -> Option<i128> {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    i128::try_from(a).ok()
+}
+
+/// Exact fraction over `i128`, always kept in lowest terms with a positive
+/// denominator. The operators panic on overflow rather than wrapping, the
+/// `checked_` methods return `None` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// `None` if `den` is zero or the fraction does not fit once the sign
+    /// is moved to the numerator.
+    pub fn new(num: i128, den: i128) -> Option<Rational> {
+        if den == 0 {
+            return None;
+        }
+
+        let g = gcd_i128(num, den)?;
+        let (num, den) = (num / g, den / g);
+        if den < 0 {
+            return Some(Rational {
+                num: num.checked_neg()?,
+                den: den.checked_neg()?,
+            });
+        }
+
+        Some(Rational { num, den })
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+
+    /// Largest integer not above the fraction.
+    pub fn floor(&self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    /// Smallest integer not below the fraction.
+    pub fn ceil(&self) -> i128 {
+        self.floor() + i128::from(self.num.rem_euclid(self.den) != 0)
+    }
+
+    /// `None` for zero, or if the numerator of the result overflows.
+    pub fn recip(&self) -> Option<Rational> {
+        Rational::new(self.den, self.num)
+    }
+
+    pub fn checked_add(self, other: Rational) -> Option<Rational> {
+        let g = gcd_i128(self.den, other.den)?;
+        let lcm = (self.den / g).checked_mul(other.den)?;
+        let a = self.num.checked_mul(lcm / self.den)?;
+        let b = other.num.checked_mul(lcm / other.den)?;
+        Rational::new(a.checked_add(b)?, lcm)
+    }
+
+    pub fn checked_neg(self) -> Option<Rational> {
+        Some(Rational {
+            num: self.num.checked_neg()?,
+            den: self.den,
+        })
+    }
+
+    pub fn checked_sub(self, other: Rational) -> Option<Rational> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+        Rational::product(self.num, self.den, other.num, other.den)
+    }
+
+    /// `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Rational) -> Option<Rational> {
+        if other.is_zero() {
+            return None;
+        }
+
+        Rational::product(self.num, self.den, other.den, other.num)
+    }
+
+    /// `(a / b) * (c / d)`, cancelling across before multiplying to keep the
+    /// terms small.
+    fn product(a: i128, b: i128, c: i128, d: i128) -> Option<Rational> {
+        let g1 = gcd_i128(a, d)?.max(1);
+        let g2 = gcd_i128(c, b)?.max(1);
+        Rational::new((a / g1).checked_mul(c / g2)?, (b / g2).checked_mul(d / g1)?)
+    }
+}
+
+fn checked<T>(value: Option<T>) -> T {
+    value.expect("rational overflow")
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Rational {
+        Rational { num: n, den: 1 }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Rational {
+        Rational::from(n as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        checked(self.checked_add(other))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        checked(self.checked_neg())
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        checked(self.checked_sub(other))
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        checked(self.checked_mul(other))
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Rational {
+        assert!(!other.is_zero(), "zero denominator");
+        checked(self.checked_div(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let a = checked(self.num.checked_mul(other.den));
+        let b = checked(other.num.checked_mul(self.den));
+        a.cmp(&b)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// Solves `matrix * x = rhs` exactly by Gaussian elimination. Returns `None`
+/// if the system is not square, has no unique solution, or overflows.
+pub fn solve_linear(
+    mut matrix: Vec<Vec<Rational>>,
+    mut rhs: Vec<Rational>,
+) -> Option<Vec<Rational>> {
+    let n = rhs.len();
+    if matrix.len() != n || matrix.iter().any(|row| row.len() != n) {
+        return None;
+    }
+
+    for col in 0..n {
+        let pivot = (col..n).find(|r| !matrix[*r][col].is_zero())?;
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        for row in 0..n {
+            if row == col || matrix[row][col].is_zero() {
+                continue;
+            }
+
+            let factor = matrix[row][col].checked_div(matrix[col][col])?;
+            let pivot_row = matrix[col].clone();
+            for (v, p) in matrix[row].iter_mut().zip(pivot_row).skip(col) {
+                *v = v.checked_sub(factor.checked_mul(p)?)?;
+            }
+            rhs[row] = rhs[row].checked_sub(factor.checked_mul(rhs[col])?)?;
+        }
+    }
+
+    (0..n)
+        .map(|i| rhs[i].checked_div(matrix[i][i]))
+        .collect::<Option<Vec<Rational>>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(num: i128, den: i128) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn test_difference_table() {
+        let table = DifferenceTable::new(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(
+            table.rows(),
+            &[
+                vec![1, 3, 6, 10, 15, 21],
+                vec![2, 3, 4, 5, 6],
+                vec![1, 1, 1, 1],
+                vec![0, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_difference_table_empty() {
+        assert_eq!(DifferenceTable::new(&[]), None);
+    }
+
+    #[test]
+    fn test_difference_table_overflow() {
+        assert_eq!(DifferenceTable::new(&[i64::MIN, i64::MAX]), None);
+    }
+
+    #[test]
+    fn test_extrapolate_forward() {
+        assert_eq!(extrapolate_forward(&[0, 3, 6, 9, 12, 15]), Some(18));
+        assert_eq!(extrapolate_forward(&[1, 3, 6, 10, 15, 21]), Some(28));
+        assert_eq!(extrapolate_forward(&[10, 13, 16, 21, 30, 45]), Some(68));
+        assert_eq!(extrapolate_forward(&[7]), Some(7));
+    }
+
+    #[test]
+    fn test_extrapolate_forward_by() {
+        let table = DifferenceTable::new(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(table.extrapolate_forward_by(0), Some(21));
+        assert_eq!(table.extrapolate_forward_by(1), Some(28));
+        assert_eq!(table.extrapolate_forward_by(4), Some(55));
+
+        // triangular numbers, T(n) = n(n+1)/2 at index n - 1
+        let n = 1_000_000i64;
+        assert_eq!(
+            table.extrapolate_forward_by(n as u64 - 6),
+            Some(n * (n + 1) / 2)
+        );
+
+        let table = DifferenceTable::new(&[5, 5, 5]).unwrap();
+        assert_eq!(table.extrapolate_forward_by(1000), Some(5));
+        assert_eq!(table.extrapolate_forward_by(u64::MAX), Some(5));
+
+        let table = DifferenceTable::new(&[0, 1, 2]).unwrap();
+        assert_eq!(
+            table.extrapolate_forward_by(5_000_000_000),
+            Some(5_000_000_002)
+        );
+
+        // squares, n^2 at index n
+        let table = DifferenceTable::new(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(
+            table.extrapolate_forward_by(3_000_000_000 - 3),
+            Some(9_000_000_000_000_000_000)
+        );
+        assert_eq!(table.extrapolate_forward_by(4_000_000_000 - 3), None);
+    }
+
+    #[test]
+    fn test_extrapolate_backward() {
+        assert_eq!(extrapolate_backward(&[0, 3, 6, 9, 12, 15]), Some(-3));
+        assert_eq!(extrapolate_backward(&[1, 3, 6, 10, 15, 21]), Some(0));
+        assert_eq!(extrapolate_backward(&[10, 13, 16, 21, 30, 45]), Some(5));
+        assert_eq!(extrapolate_backward(&[7]), Some(7));
+    }
+
+    #[test]
+    fn test_extrapolate_overflow() {
+        assert_eq!(extrapolate_forward(&[i64::MAX - 1, i64::MAX]), None);
+        assert_eq!(extrapolate_backward(&[i64::MIN, i64::MIN + 1]), None);
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(3847, 3919), Some(3847 * 3919));
+        assert_eq!(lcm(0, 5), Some(0));
+        assert_eq!(lcm(u64::MAX, 2), None);
+        assert_eq!(lcm(u64::MAX, u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_rational() {
+        let half = frac(2, 4);
+        assert_eq!(half, frac(-1, -2));
+        assert_eq!((half.numerator(), half.denominator()), (1, 2));
+        assert_eq!(frac(3, -6), -half);
+        assert_eq!(half + frac(1, 3), frac(5, 6));
+        assert_eq!(half - frac(1, 3), frac(1, 6));
+        assert_eq!(half * frac(4, 3), frac(2, 3));
+        assert_eq!(half / frac(1, 4), Rational::from(2i64));
+        assert_eq!(frac(6, 3).to_integer(), Some(2));
+        assert_eq!(half.to_integer(), None);
+        assert!(frac(-1, 2) < frac(1, 3));
+        assert!(frac(2, 3) > frac(3, 5));
+        assert_eq!(frac(-3, 6).to_string(), "-1/2");
+        assert_eq!(Rational::from(7i64).to_string(), "7");
+    }
+
+    #[test]
+    fn test_rational_extremes() {
+        assert_eq!(Rational::new(i128::MIN, 1), Some(Rational::from(i128::MIN)));
+        assert_eq!(
+            Rational::new(i128::MIN, -2),
+            Some(Rational::from(1i128 << 126))
+        );
+        assert_eq!(Rational::new(i128::MIN, -1), None);
+        assert_eq!(Rational::new(1, 0), None);
+        assert_eq!(Rational::ZERO.recip(), None);
+        assert_eq!(Rational::from(i128::MIN).recip(), None);
+        assert_eq!(frac(-2, 3).recip(), Some(frac(-3, 2)));
+        assert_eq!((frac(-3, 2).floor(), frac(-3, 2).ceil()), (-2, -1));
+        assert_eq!((frac(7, 2).floor(), frac(7, 2).ceil()), (3, 4));
+        assert_eq!(Rational::from(i128::MIN).floor(), i128::MIN);
+        assert_eq!(Rational::from(i128::MAX).ceil(), i128::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "zero denominator")]
+    fn test_rational_zero_denominator() {
+        let _ = Rational::ONE / Rational::ZERO;
+    }
+
+    #[test]
+    #[should_panic(expected = "rational overflow")]
+    fn test_rational_overflow() {
+        let big = Rational::from(i128::MAX / 2);
+        let _ = big * Rational::from(3i64);
+    }
+
+    #[test]
+    fn test_solve_linear() {
+        let r = |n: i64| Rational::from(n);
+
+        // x + y = 3, x - y = 1
+        assert_eq!(
+            solve_linear(vec![vec![r(1), r(1)], vec![r(1), r(-1)]], vec![r(3), r(1)]),
+            Some(vec![r(2), r(1)])
+        );
+
+        // 2x = 1, needing a row swap for the first pivot
+        assert_eq!(
+            solve_linear(vec![vec![r(0), r(1)], vec![r(2), r(0)]], vec![r(5), r(1)]),
+            Some(vec![frac(1, 2), r(5)])
+        );
+
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        assert_eq!(
+            solve_linear(
+                vec![
+                    vec![r(2), r(1), r(-1)],
+                    vec![r(-3), r(-1), r(2)],
+                    vec![r(-2), r(1), r(2)],
+                ],
+                vec![r(8), r(-11), r(-3)]
+            ),
+            Some(vec![r(2), r(3), r(-1)])
+        );
+    }
+
+    #[test]
+    fn test_rational_checked() {
+        let big = Rational::from(i128::MAX / 2);
+        let three = Rational::from(3i64);
+        assert_eq!(frac(1, 2).checked_add(frac(1, 3)), Some(frac(5, 6)));
+        assert_eq!(big.checked_mul(three), None);
+        assert_eq!(big.checked_add(big).and_then(|b| b.checked_add(big)), None);
+        assert_eq!(
+            (-big).checked_sub(big).and_then(|b| b.checked_sub(big)),
+            None
+        );
+        assert_eq!(Rational::ONE.checked_div(Rational::ZERO), None);
+        assert_eq!(three.checked_div(big), Some(frac(3, i128::MAX / 2)));
+    }
+
+    #[test]
+    fn test_solve_linear_overflow() {
+        let big = Rational::from(i128::MAX / 2);
+        let r = |n: i64| Rational::from(n);
+        assert_eq!(
+            solve_linear(vec![vec![r(1), r(1)], vec![r(3), big]], vec![big, r(0)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_solve_linear_singular() {
+        let r = |n: i64| Rational::from(n);
+        assert_eq!(
+            solve_linear(vec![vec![r(1), r(2)], vec![r(2), r(4)]], vec![r(3), r(6)]),
+            None
+        );
+        assert_eq!(solve_linear(vec![vec![r(1)]], vec![r(1), r(2)]), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let table = DifferenceTable::new(&[0, 3, 6]).unwrap();
+        assert_eq!(table.to_string(), "0   3   6\n  3   3\n    0\n");
+    }
+}
@@ -0,0 +1,139 @@
+/// Number of ways to fill the unknown cells (`?`) of a nonogram line so that
+/// its filled runs (`#`, separated by `.`) are exactly `groups`, in order.
+///
+/// Tabulates the number of arrangements of each suffix of the line against
+/// each suffix of the groups, so it runs in `O(len * groups)`. `None` if the
+/// count does not fit in a `u64`.
+pub fn count_arrangements(line: &[u8], groups: &[usize]) -> Option<u64> {
+    let n = line.len();
+    let g = groups.len();
+
+    // empty_before[i] is the number of '.' cells in line[..i]
+    let mut empty_before = vec![0; n + 1];
+    for (i, c) in line.iter().enumerate() {
+        empty_before[i + 1] = empty_before[i] + usize::from(*c == b'.');
+    }
+
+    // ways[i][j] counts arrangements of line[i..] against groups[j..], or is
+    // None if that count overflowed
+    let mut ways = vec![vec![Some(0u64); g + 1]; n + 2];
+    ways[n][g] = Some(1);
+    ways[n + 1][g] = Some(1);
+
+    for i in (0..n).rev() {
+        for j in (0..=g).rev() {
+            let mut count = Some(0);
+            if line[i] != b'#' {
+                count = ways[i + 1][j];
+            }
+
+            if line[i] != b'.' && j < g && groups[j] <= n - i {
+                let end = i + groups[j];
+                if empty_before[end] == empty_before[i] && (end == n || line[end] != b'#') {
+                    count = count
+                        .zip(ways[end + 1][j + 1])
+                        .and_then(|(a, b)| a.checked_add(b));
+                }
+            }
+
+            ways[i][j] = count;
+        }
+    }
+
+    ways[0][0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+
+    fn runs(line: &[u8]) -> Vec<usize> {
+        line.split(|c| *c == b'.')
+            .filter(|r| !r.is_empty())
+            .map(|r| r.len())
+            .collect::<Vec<usize>>()
+    }
+
+    fn brute_force(line: &[u8], groups: &[usize]) -> u64 {
+        let unknown = line
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == b'?')
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+
+        let mut count = 0;
+        for mask in 0..1u64 << unknown.len() {
+            let mut filled = line.to_vec();
+            for (bit, i) in unknown.iter().enumerate() {
+                filled[*i] = if mask & (1 << bit) != 0 { b'#' } else { b'.' };
+            }
+
+            if runs(&filled) == groups {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    #[test]
+    fn test_count_arrangements() {
+        assert_eq!(count_arrangements(b"???.###", &[1, 1, 3]), Some(1));
+        assert_eq!(count_arrangements(b".??..??...?##.", &[1, 1, 3]), Some(4));
+        assert_eq!(
+            count_arrangements(b"?#?#?#?#?#?#?#?", &[1, 3, 1, 6]),
+            Some(1)
+        );
+        assert_eq!(count_arrangements(b"????.#...#...", &[4, 1, 1]), Some(1));
+        assert_eq!(
+            count_arrangements(b"????.######..#####.", &[1, 6, 5]),
+            Some(4)
+        );
+        assert_eq!(count_arrangements(b"?###????????", &[3, 2, 1]), Some(10));
+    }
+
+    #[test]
+    fn test_count_arrangements_edges() {
+        assert_eq!(count_arrangements(b"", &[]), Some(1));
+        assert_eq!(count_arrangements(b"", &[1]), Some(0));
+        assert_eq!(count_arrangements(b"...", &[]), Some(1));
+        assert_eq!(count_arrangements(b"???", &[]), Some(1));
+        assert_eq!(count_arrangements(b"#", &[]), Some(0));
+        assert_eq!(count_arrangements(b"###", &[2]), Some(0));
+        assert_eq!(count_arrangements(b"???", &[4]), Some(0));
+        assert_eq!(count_arrangements(b"???", &[usize::MAX]), Some(0));
+    }
+
+    #[test]
+    fn test_count_arrangements_overflow() {
+        // k single cells among n unknowns fit in C(n - k + 1, k) ways
+        let line = [b'?'; 101];
+        assert_eq!(count_arrangements(&line, &[1; 34]), None);
+        assert_eq!(
+            count_arrangements(&line[..98], &[1; 33]),
+            Some(7_219_428_434_016_265_740)
+        );
+    }
+
+    #[test]
+    fn test_count_arrangements_matches_brute_force() {
+        let mut rng = Rng::new(12);
+        for _ in 0..2000 {
+            let len = rng.range(0..14);
+            let line = (0..len).map(|_| *rng.choose(b".#??")).collect::<Vec<u8>>();
+            let groups = (0..rng.range(0..5))
+                .map(|_| rng.range(1..5))
+                .collect::<Vec<usize>>();
+
+            assert_eq!(
+                count_arrangements(&line, &groups),
+                Some(brute_force(&line, &groups)),
+                "{} {:?}",
+                String::from_utf8_lossy(&line),
+                groups
+            );
+        }
+    }
+}
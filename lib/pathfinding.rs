@@ -0,0 +1,154 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Cheapest cost from any of `starts` to a state accepted by `is_goal`, along
+/// with that state. `neighbours` yields each reachable state and the cost of
+/// the move, and `heuristic` must never overestimate the remaining cost.
+pub fn astar<S, C, N, I, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(C, S)>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut best: HashMap<S, C> = HashMap::new();
+    let mut states: Vec<S> = Vec::new();
+    let mut queue = BinaryHeap::new();
+
+    for start in starts {
+        let cost = C::default();
+        if best.insert(start.clone(), cost).is_none() {
+            queue.push(Reverse((heuristic(&start), cost, states.len())));
+            states.push(start);
+        }
+    }
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let state = states[index].clone();
+        if best.get(&state).is_some_and(|b| *b < cost) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return Some((cost, state));
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if best.get(&next).is_some_and(|b| *b <= next_cost) {
+                continue;
+            }
+
+            best.insert(next.clone(), next_cost);
+            queue.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                states.len(),
+            )));
+            states.push(next);
+        }
+    }
+
+    None
+}
+
+pub fn dijkstra<S, C, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    is_goal: G,
+) -> Option<(C, S)>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, neighbours, |_| C::default(), is_goal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Direction, Grid};
+
+    fn weights(lines: &[&str]) -> Grid<u32> {
+        Grid::from_lines(lines).map(|c| c.to_digit(10).unwrap())
+    }
+
+    fn grid_neighbours(grid: &Grid<u32>, (x, y): (usize, usize)) -> Vec<((usize, usize), u32)> {
+        Direction::ALL
+            .iter()
+            .filter_map(|d| grid.step(x, y, *d))
+            .map(|(nx, ny)| ((nx, ny), *grid.get(nx, ny)))
+            .collect::<Vec<((usize, usize), u32)>>()
+    }
+
+    #[test]
+    fn test_dijkstra_grid() {
+        // 1 9 1
+        // 1 9 1
+        // 1 1 1
+        let grid = weights(&["191", "191", "111"]);
+        let goal = (2, 0);
+        let found = dijkstra([(0, 0)], |p| grid_neighbours(&grid, *p), |p| *p == goal);
+        assert_eq!(found, Some((6, goal)));
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let grid = weights(&["1163751", "1381373", "2136511", "3694931", "7463417"]);
+        let goal = (6, 4);
+        let manhattan = |(x, y): &(usize, usize)| (goal.0 - x + goal.1 - y) as u32;
+
+        let by_dijkstra = dijkstra([(0, 0)], |p| grid_neighbours(&grid, *p), |p| *p == goal);
+        let by_astar = astar(
+            [(0, 0)],
+            |p| grid_neighbours(&grid, *p),
+            manhattan,
+            |p| *p == goal,
+        );
+        assert_eq!(by_dijkstra, Some((28, goal)));
+        assert_eq!(by_astar, by_dijkstra);
+    }
+
+    #[test]
+    fn test_multiple_starts() {
+        let grid = weights(&["5115"]);
+        let found = dijkstra(
+            [(0, 0), (3, 0)],
+            |p| grid_neighbours(&grid, *p),
+            |p| *p == (2, 0),
+        );
+        assert_eq!(found, Some((1, (2, 0))));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let found = dijkstra(
+            [0u32],
+            |n| [(n + 1, 1u32)].into_iter().filter(|(n, _)| *n < 10),
+            |n| *n == 20,
+        );
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn test_start_is_goal() {
+        assert_eq!(
+            dijkstra([3u32], |_| Vec::<(u32, u64)>::new(), |n| *n == 3),
+            Some((0, 3))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::answer::{Answer, AnswerError};
+
+pub(crate) const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+pub(crate) const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+/// The bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+pub const BAG: [u64; 3] = [12, 13, 14];
+
+/// The value of each position in `line` that starts a digit, or a number
+/// word when `words` is set.
+fn line_values(line: &str, words: bool) -> Vec<u64> {
+    line.char_indices()
+        .filter_map(|(i, c)| {
+            let rest = &line[i..];
+            if let Some(d) = c.to_digit(10) {
+                return Some(u64::from(d));
+            }
+            if !words {
+                return None;
+            }
+            WORDS
+                .iter()
+                .position(|w| rest.starts_with(w))
+                .map(|n| n as u64 + 1)
+        })
+        .collect()
+}
+
+/// Reference day1 calibration value of a line, zero if it has no number.
+pub fn calibration(line: &str, words: bool) -> u64 {
+    let values = line_values(line, words);
+    match (values.first(), values.last()) {
+        (Some(first), Some(last)) => first * 10 + last,
+        _ => 0,
+    }
+}
+
+/// The fewest cubes of each colour needed for a game record. Like day2, a
+/// colour repeated within a set counts with its last value.
+pub fn fewest_cubes(record: &str) -> [u64; 3] {
+    let mut fewest = [0; 3];
+    let sets = record.split_once(':').map_or("", |(_, s)| s);
+    for set in sets.split(';') {
+        let mut shown = [0; 3];
+        for cube in set.split(',') {
+            let mut parts = cube.split_whitespace();
+            if let (Some(count), Some(colour)) = (parts.next(), parts.next()) {
+                if let (Ok(count), Some(c)) = (
+                    count.parse::<u64>(),
+                    COLOURS.iter().position(|&n| n == colour),
+                ) {
+                    shown[c] = count;
+                }
+            }
+        }
+        for (f, s) in fewest.iter_mut().zip(shown) {
+            *f = (*f).max(s);
+        }
+    }
+    fewest
+}
+
+/// Reference day2 answers: the sum of ids possible with [`BAG`] and the sum
+/// of the powers of the fewest cubes per game.
+pub fn games(input: &str) -> Result<(u64, u64), AnswerError> {
+    let (mut ids, mut power) = (Answer::ZERO, Answer::ZERO);
+    for record in input.lines() {
+        let fewest = fewest_cubes(record);
+        let id = record
+            .split_once(':')
+            .and_then(|(g, _)| g.trim_start_matches("Game ").parse::<u64>().ok())
+            .unwrap_or_default();
+        if fewest.iter().zip(BAG).all(|(&f, b)| f <= b) {
+            ids = ids.checked_add(id, "sum of ids")?;
+        }
+        let game_power = Answer::product(fewest, "smallest bag power")?;
+        power = power.checked_add(game_power, "smallest bag power sum")?;
+    }
+    Ok((ids.value(), power.value()))
+}
+
+/// Reference day3 part numbers: every number with a symbol among its
+/// neighbours. Like day3, anything but `.` counts as a symbol, so the digits
+/// of a neighbouring number do too. Fails on a number too long for a `u64`.
+pub fn part_numbers(input: &str) -> Result<Vec<u64>, AnswerError> {
+    let rows = input.lines().map(str::as_bytes).collect::<Vec<&[u8]>>();
+    let is_symbol = |x: usize, y: usize| {
+        rows.get(y)
+            .and_then(|r| r.get(x))
+            .is_some_and(|&c| c != b'.')
+    };
+
+    let mut nums = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            if !row[x].is_ascii_digit() {
+                x += 1;
+                continue;
+            }
+
+            let start = x;
+            while x < row.len() && row[x].is_ascii_digit() {
+                x += 1;
+            }
+            let adjacent = (y.saturating_sub(1)..=y + 1).any(|ny| {
+                (start.saturating_sub(1)..=x)
+                    .filter(|nx| ny != y || !(start..x).contains(nx))
+                    .any(|nx| is_symbol(nx, ny))
+            });
+            if adjacent {
+                let digits = std::str::from_utf8(&row[start..x]).unwrap();
+                nums.push(Answer::parse_digits(digits, "part number")?.value());
+            }
+        }
+    }
+    Ok(nums)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_day1_examples() {
+        let part1 = ["1abc2", "pqr3stu8vwx", "a1b2c3d4e5f", "treb7uchet"];
+        assert_eq!(
+            part1.iter().map(|l| calibration(l, false)).sum::<u64>(),
+            142
+        );
+
+        let part2 = [
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "4nineeightseven2",
+            "zoneight234",
+            "7pqrstsixteen",
+        ];
+        assert_eq!(part2.iter().map(|l| calibration(l, true)).sum::<u64>(), 281);
+        assert_eq!(calibration("twone", true), 21);
+        assert_eq!(calibration("abc", true), 0);
+        assert_eq!(calibration("½é1", true), 11);
+    }
+
+    #[test]
+    fn test_reference_day2_example() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n\
+                     Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n\
+                     Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red\n\
+                     Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red\n\
+                     Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green\n";
+        assert_eq!(games(input), Ok((8, 2286)));
+        assert_eq!(fewest_cubes("Game 1: 5 red, 2 red; 3 red"), [3, 0, 0]);
+    }
+
+    #[test]
+    fn test_reference_day3_example() {
+        let input = "467..114..\n...*......\n..35..633.\n......#...\n617*......\n\
+                     .....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..\n";
+        assert_eq!(part_numbers(input).unwrap().iter().sum::<u64>(), 4361);
+        assert_eq!(part_numbers("12\n34\n"), Ok(vec![12, 34]));
+        assert_eq!(part_numbers("12.\n...\n"), Ok(Vec::new()));
+        assert!(part_numbers("123456789012345678901*\n").is_err());
+    }
+}